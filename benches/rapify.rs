@@ -0,0 +1,68 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use armalint::config::simplify::{Array, ArrayElement, Class, Config, Entry};
+
+fn properties(count: usize) -> Vec<(String, Entry)> {
+    (0..count)
+        .map(|i| {
+            let entry = match i % 4 {
+                0 => Entry::Str(format!("value {}", i)),
+                1 => Entry::Float(i as f32 / 3.0),
+                2 => Entry::Int(i as i32),
+                _ => Entry::Array(Array {
+                    expand: false,
+                    elements: vec![ArrayElement::Str("a".to_string()), ArrayElement::Int(i as i32)],
+                }),
+            };
+            (format!("property{}", i), entry)
+        })
+        .collect()
+}
+
+fn class(entries: Vec<(String, Entry)>) -> Class {
+    Class {
+        parent: String::new(),
+        external: false,
+        deletion: false,
+        entries,
+    }
+}
+
+/// Classes nested `depth` levels deep, each with `width` child classes
+fn wide(depth: usize, width: usize) -> Class {
+    let mut entries = properties(8);
+    if depth > 0 {
+        for i in 0..width {
+            entries.push((format!("Class{}", i), Entry::Class(wide(depth - 1, width))));
+        }
+    }
+    class(entries)
+}
+
+/// A single chain of classes nested `depth` levels deep
+fn deep(depth: usize) -> Class {
+    let mut root = class(properties(16));
+    for _ in 0..depth {
+        let mut entries = properties(16);
+        entries.push(("Inner".to_string(), Entry::Class(root)));
+        root = class(entries);
+    }
+    root
+}
+
+fn config(root: Class) -> Config {
+    Config {
+        root,
+        enums: Vec::new(),
+    }
+}
+
+fn write(c: &mut Criterion) {
+    let wide = config(wide(6, 5));
+    let deep = config(deep(250));
+    c.bench_function("write wide", |b| b.iter(|| wide.write_rapified(&mut Vec::new()).unwrap()));
+    c.bench_function("write deep", |b| b.iter(|| deep.write_rapified(&mut Vec::new()).unwrap()));
+}
+
+criterion_group!(benches, write);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::commands::Project;
+use crate::config::simplify::Config;
+use crate::config::Renderer;
+use crate::{ArmaLintError, Command};
+
+pub struct Derapify {}
+impl Command for Derapify {
+    fn register(&self) -> clap::App {
+        clap::SubCommand::with_name("derapify")
+            .version(*crate::VERSION)
+            .about("Convert a rapified config back into text")
+            .arg(clap::Arg::with_name("file").help("Rapified config to read").required(true))
+            .arg(
+                clap::Arg::with_name("output")
+                    .help("File to write, defaults to the input with a `.cpp` extension, `-` prints the config"),
+            )
+    }
+
+    fn run(&self, args: &clap::ArgMatches) -> Result<(), ArmaLintError> {
+        let path = PathBuf::from(args.value_of("file").unwrap());
+        let output = match args.value_of("output") {
+            Some(output) => PathBuf::from(output),
+            None => path.with_extension("cpp"),
+        };
+        if output == path {
+            return Err(ArmaLintError::InvalidInput(format!(
+                "Refusing to overwrite the input `{}`",
+                path.display()
+            )));
+        }
+        let config = Config::read_rapified(&mut open_file!(path)?)?;
+        let options = Project::current()?.render;
+        let mut rendered = Renderer::new(options).render_config(&config)?;
+        rendered.push('\n');
+        if output.to_str() == Some("-") {
+            print!("{}", rendered);
+        } else {
+            create_file!(output)?.write_all(rendered.as_bytes())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+
+use crate::commands::Project;
+use crate::config::{RenderOptions, Renderer};
+use crate::{ArmaLintError, Command};
+
+pub struct Fmt {}
+impl Fmt {
+    /// Formats the source of a config without preprocessing it
+    ///
+    /// Defines, macros and includes are kept as written, included files are not read.
+    pub fn format(file: &str, source: &str, options: RenderOptions) -> Result<String, ArmaLintError> {
+        let cst =
+            crate::config::parse_cst_with_resolver(file, std::env::current_dir()?, source, &|_: &str, wd: &Path, _| {
+                Ok((String::new(), wd.to_path_buf()))
+            })?;
+        let mut output = Renderer::new(options).render(cst)?;
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    fn print_diff(path: &Path, original: &str, formatted: &str) {
+        let mut line = 0;
+        let mut in_hunk = false;
+        for result in diff::lines(original, formatted) {
+            match result {
+                diff::Result::Both(_, _) => {
+                    line += 1;
+                    in_hunk = false;
+                }
+                diff::Result::Left(l) => {
+                    line += 1;
+                    if !in_hunk {
+                        println!("Diff in {} at line {}:", path.display(), line);
+                        in_hunk = true;
+                    }
+                    println!("{}", format!("-{}", l).red());
+                }
+                diff::Result::Right(r) => {
+                    if !in_hunk {
+                        println!("Diff in {} at line {}:", path.display(), line + 1);
+                        in_hunk = true;
+                    }
+                    println!("{}", format!("+{}", r).green());
+                }
+            }
+        }
+    }
+}
+
+impl Command for Fmt {
+    fn register(&self) -> clap::App {
+        clap::SubCommand::with_name("fmt")
+            .version(*crate::VERSION)
+            .about("Format config files")
+            .arg(
+                clap::Arg::with_name("files")
+                    .help("Files to format")
+                    .required(true)
+                    .multiple(true),
+            )
+            .arg(
+                clap::Arg::with_name("check")
+                    .help("Show a diff and fail if a file is not formatted, without writing it")
+                    .long("check")
+                    .conflicts_with("stdout"),
+            )
+            .arg(
+                clap::Arg::with_name("stdout")
+                    .help("Print the formatted files instead of writing them")
+                    .long("stdout"),
+            )
+    }
+
+    fn run(&self, args: &clap::ArgMatches) -> Result<(), ArmaLintError> {
+        let options = Project::current()?.render;
+        let mut unformatted = 0;
+        for file in args.values_of("files").unwrap() {
+            let path = PathBuf::from(file);
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+            if !["cpp", "hpp", "ext"].contains(&extension) {
+                return Err(ArmaLintError::InvalidInput(format!("Unable to format `{}` files", extension)));
+            }
+            let mut content = String::new();
+            open_file!(path)?.read_to_string(&mut content)?;
+            let formatted = Fmt::format(file, &content, options)?;
+            if args.is_present("stdout") {
+                print!("{}", formatted);
+            } else if content.replace("\r", "") != formatted {
+                if args.is_present("check") {
+                    Fmt::print_diff(&path, &content.replace("\r", ""), &formatted);
+                    unformatted += 1;
+                } else {
+                    create_file!(path)?.write_all(formatted.as_bytes())?;
+                }
+            }
+        }
+        if unformatted > 0 {
+            return Err(ArmaLintError::GENERIC(format!(
+                "{} file{} not formatted",
+                unformatted,
+                if unformatted == 1 { " is" } else { "s are" }
+            )));
+        }
+        Ok(())
+    }
+}
@@ -1,44 +1,407 @@
+use std::collections::BTreeMap;
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
 
+use crate::commands::{Project, Rapify, Sources};
+use crate::config::simplify::Config;
+use crate::config::{Diagnostic, Files, IncludeResolver, Severity};
+use crate::pbo::Pbo;
 use crate::{ArmaLintError, Command};
 
+/// How the results of a lint are written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Colored text with the source of each diagnostic, printed as the files are linted
+    Human,
+    Json,
+    Sarif,
+    Checkstyle,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 4] = ["human", "json", "sarif", "checkstyle"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(Format::Human),
+            "json" => Some(Format::Json),
+            "sarif" => Some(Format::Sarif),
+            "checkstyle" => Some(Format::Checkstyle),
+            _ => None,
+        }
+    }
+}
+
+/// Result of linting a single file
+struct Linted {
+    name: String,
+    /// Lines of the human format about the file
+    status: Vec<String>,
+    files: Files,
+    diagnostics: Vec<Diagnostic>,
+    /// Error that stopped the file from being linted
+    error: Option<ArmaLintError>,
+}
+
+impl Linted {
+    fn new(name: &str) -> Self {
+        Linted {
+            name: name.to_string(),
+            status: Vec::new(),
+            files: Files::new(),
+            diagnostics: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn failed(name: &str, error: ArmaLintError) -> Self {
+        Linted {
+            error: Some(error),
+            ..Linted::new(name)
+        }
+    }
+}
+
+/// Diagnostics of every linted file, the human format prints each file as it is added
+struct Output {
+    format: Format,
+    diagnostics: Vec<Diagnostic>,
+    files: usize,
+    failed: usize,
+    /// Only one file is linted, its error is returned instead of being printed
+    single: bool,
+    error: Option<ArmaLintError>,
+}
+
+impl Output {
+    fn add(&mut self, linted: Linted) {
+        if self.format == Format::Human {
+            println!("\n{}", linted.name);
+            for line in &linted.status {
+                println!("{}", line);
+            }
+            for diagnostic in &linted.diagnostics {
+                diagnostic!(linted.files, diagnostic);
+            }
+        }
+        self.files += 1;
+        if let Some(err) = linted.error {
+            self.failed += 1;
+            if self.single {
+                self.error = Some(err);
+            } else if self.format == Format::Human {
+                err.error();
+            }
+        }
+        self.diagnostics.extend(linted.diagnostics);
+    }
+
+    fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error).count()
+    }
+
+    /// Prints a summary in the human format, or every diagnostic in a machine-readable format
+    fn finish(&self) {
+        match self.format {
+            Format::Human => {
+                let errors = self.errors();
+                let mut summary = format!(
+                    "\nLinted {}: {}, {}",
+                    plural(self.files, "file"),
+                    plural(errors, "error"),
+                    plural(self.diagnostics.len() - errors, "warning")
+                );
+                if self.failed > 0 {
+                    summary.push_str(&format!(", {} could not be linted", plural(self.failed, "file")));
+                }
+                println!("{}", summary);
+            }
+            Format::Json => println!("{}", crate::config::output::json(&self.diagnostics)),
+            Format::Sarif => println!("{}", crate::config::output::sarif(&self.diagnostics)),
+            Format::Checkstyle => println!("{}", crate::config::output::checkstyle(&self.diagnostics)),
+        }
+    }
+}
+
+fn plural(count: usize, name: &str) -> String {
+    format!("{} {}{}", count, name, if count == 1 { "" } else { "s" })
+}
+
 pub struct Lint {}
+impl Lint {
+    /// Lints a file, a PBO has a result for every config in it
+    fn file(path: &Path, project: &Project, duplicate_includes: bool) -> Vec<Linted> {
+        let name = path.display().to_string();
+        let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        let result = match extension.as_deref() {
+            Some("pbo") => return Lint::pbo(path, project, duplicate_includes),
+            Some("cpp") | Some("hpp") | Some("ext") | Some("sqm") | Some("bin") => (|| {
+                let mut data = Vec::new();
+                open_file!(path)?.read_to_end(&mut data)?;
+                if data.starts_with(b"\0raP") {
+                    return Lint::rapified(&name, &data, project);
+                }
+                if extension.as_deref() == Some("bin") {
+                    return Err(ArmaLintError::InvalidInput(format!("`{}` is not a rapified config", name)));
+                }
+                let resolver = project.with_includes(Rapify::resolver(path, &[])?)?;
+                let wd = path.parent().map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+                let content = String::from_utf8_lossy(&data);
+                Lint::source(&name, wd, &content, &resolver, project, duplicate_includes)
+            })(),
+            Some(extension) => Err(ArmaLintError::InvalidInput(format!(
+                "Unable to process `{}` files",
+                extension
+            ))),
+            None => Err(ArmaLintError::InvalidInput(format!(
+                "Unable to process `{}`, it has no extension",
+                name
+            ))),
+        };
+        vec![result.unwrap_or_else(|err| Linted::failed(&name, err))]
+    }
+
+    /// Parses and preprocesses a config with the defines and rules of a project
+    ///
+    /// A config with syntax errors is not preprocessed, only its syntax errors are reported.
+    fn source(
+        name: &str,
+        wd: PathBuf,
+        content: &str,
+        resolver: &dyn IncludeResolver,
+        project: &Project,
+        duplicate_includes: bool,
+    ) -> Result<Linted, ArmaLintError> {
+        let mut linted = Linted::new(name);
+        let ast = match crate::config::parse_with_resolver(name, wd, content, resolver) {
+            Err(ArmaLintError::SyntaxErrors(ast)) => {
+                let report = project.report(ast.report.unwrap().errors);
+                linted.status.push("Syntax: Invalid".to_string());
+                linted.diagnostics = report.warnings.into_iter().chain(report.errors).collect();
+                linted.files = ast.files;
+                return Ok(linted);
+            }
+            result => result?,
+        };
+        let mut preprocessor = project.preprocessor()?;
+        if duplicate_includes {
+            preprocessor = preprocessor.warn_duplicate_includes();
+        }
+        let mut processed = preprocessor.process(ast)?;
+        let report = processed.report.take().unwrap();
+        let report = project.report(report.warnings.into_iter().chain(report.errors));
+        linted.status.push("Syntax: Valid".to_string());
+        linted.status.push(format!(
+            "PreProcessor: {}",
+            if report.errors.is_empty() { "Valid" } else { "Invalid" }
+        ));
+        linted.diagnostics = report.warnings.into_iter().chain(report.errors).collect();
+        linted.files = processed.files;
+        Ok(linted)
+    }
+
+    /// Derapifies a config and reports problems with its inheritance
+    fn rapified(name: &str, data: &[u8], project: &Project) -> Result<Linted, ArmaLintError> {
+        let config = Config::read_rapified(&mut std::io::Cursor::new(data))?;
+        let resolved = config.resolve();
+        let report = project.report(resolved.diagnostics.iter().map(|d| d.diagnostic(name)));
+        let mut linted = Linted::new(name);
+        linted.status.push(format!(
+            "Rapified: {}",
+            if report.errors.is_empty() { "Valid" } else { "Invalid" }
+        ));
+        linted.diagnostics = report.warnings.into_iter().chain(report.errors).collect();
+        Ok(linted)
+    }
+
+    /// Lints every config in a PBO, includes are read from inside the PBO
+    ///
+    /// Files are named by their path inside the PBO, `main.pbo/config.cpp`.
+    fn pbo(path: &Path, project: &Project, duplicate_includes: bool) -> Vec<Linted> {
+        let pbo = match open_file!(path).map_err(ArmaLintError::from).and_then(Pbo::read) {
+            Ok(pbo) => pbo,
+            Err(err) => return vec![Linted::failed(&path.display().to_string(), err)],
+        };
+        let mut results = Vec::new();
+        for file in &pbo.files {
+            let name = format!("{}/{}", path.display(), file.name.replace('\\', "/"));
+            let extension = Path::new(&name)
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or_default()
+                .to_lowercase();
+            let result = match extension.as_str() {
+                "cpp" | "hpp" | "ext" | "sqm" if !file.data.starts_with(b"\0raP") => {
+                    let full = pbo.path(file);
+                    let wd = PathBuf::from(full.rfind('/').map_or("", |i| &full[..i]));
+                    let content = String::from_utf8_lossy(&file.data);
+                    Lint::source(&name, wd, &content, &pbo, project, duplicate_includes)
+                }
+                "bin" | "sqm" if file.data.starts_with(b"\0raP") => Lint::rapified(&name, &file.data, project),
+                _ => continue,
+            };
+            results.push(result.unwrap_or_else(|err| Linted::failed(&name, err)));
+        }
+        results
+    }
+
+    /// Lints files on `jobs` threads, `handle` gets the results in the order of the files
+    fn parallel(
+        paths: &[PathBuf],
+        jobs: usize,
+        project: &Project,
+        duplicate_includes: bool,
+        handle: &mut dyn FnMut(Linted),
+    ) {
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::scope(|scope| {
+            for _ in 0..jobs.clamp(1, paths.len().max(1)) {
+                let (next, sender) = (&next, sender.clone());
+                std::thread::Builder::new()
+                    // Deeply nested configs need the same stack as the main thread
+                    .stack_size(8 * 1024 * 1024)
+                    .spawn_scoped(scope, move || loop {
+                        let i = next.fetch_add(1, Ordering::SeqCst);
+                        if i >= paths.len() || sender.send((i, Lint::file(&paths[i], project, duplicate_includes))).is_err() {
+                            break;
+                        }
+                    })
+                    .unwrap();
+            }
+            drop(sender);
+            let mut pending = BTreeMap::new();
+            let mut current = 0;
+            for (i, results) in receiver {
+                pending.insert(i, results);
+                while let Some(results) = pending.remove(&current) {
+                    results.into_iter().for_each(&mut *handle);
+                    current += 1;
+                }
+            }
+        });
+    }
+}
+
 impl Command for Lint {
     fn register(&self) -> clap::App {
         clap::SubCommand::with_name("lint")
             .version(*crate::VERSION)
-            .about("Lint a file")
-            .arg(clap::Arg::with_name("file").help("File to lint").required(true))
+            .about("Lint config files")
+            .arg(
+                clap::Arg::with_name("paths")
+                    .help(
+                        "Files, directories or glob patterns to lint, a PBO lints every config in it. Defaults to \
+                         the files of the project",
+                    )
+                    .multiple(true),
+            )
+            .arg(
+                clap::Arg::with_name("duplicate-includes")
+                    .help("Warn about files included more than once without an include guard")
+                    .long("duplicate-includes"),
+            )
+            .arg(
+                clap::Arg::with_name("deny-warnings")
+                    .help("Fail with exit code 2 when there are any warnings")
+                    .long("deny-warnings"),
+            )
+            .arg(
+                clap::Arg::with_name("max-warnings")
+                    .help(
+                        "Fail with exit code 2 when there are more warnings than this. Without this or \
+                         `--deny-warnings` warnings don't fail the run, so existing projects can adopt lint \
+                         and tighten the limit step by step",
+                    )
+                    .long("max-warnings")
+                    .takes_value(true)
+                    .value_name("N")
+                    .validator(number),
+            )
+            .arg(
+                clap::Arg::with_name("jobs")
+                    .help("Number of files linted at the same time, defaults to the number of CPUs")
+                    .short("j")
+                    .long("jobs")
+                    .takes_value(true)
+                    .value_name("N")
+                    .validator(number),
+            )
+            .arg(
+                clap::Arg::with_name("format")
+                    .help("Output format, the diagnostics of every file are written at the end except for `human`")
+                    .long("format")
+                    .takes_value(true)
+                    .possible_values(&Format::NAMES)
+                    .default_value("human"),
+            )
     }
 
     fn run(&self, args: &clap::ArgMatches) -> Result<(), ArmaLintError> {
-        let path = PathBuf::from(args.value_of("file").unwrap());
-        let mut f = open_file!(path)?;
-        match path.extension().unwrap().to_str().unwrap() {
-            "cpp" | "hpp" => {
-                let mut content = String::new();
-                f.read_to_string(&mut content)?;
-                let ast = crate::config::parse(args.value_of("file").unwrap(), &content)?;
-                let mut preprocessor = crate::config::PreProcessor::new();
-                let processed = preprocessor.process(ast)?;
-                let report = processed.report.clone().unwrap();
-                println!("Syntax: Valid");
-                println!("PreProcessor: {}", if processed.valid() { "Valid" } else { "Invalid" });
-                for warning in report.warnings {
-                    node_warning!(processed.files, warning);
-                }
-                for error in report.errors {
-                    node_error!(processed.files, error);
-                }
-            }
-            _ => {
-                return Err(ArmaLintError::InvalidInput(format!(
-                    "Unable to process `{}` files",
-                    path.extension().unwrap().to_str().unwrap()
-                )))
-            }
+        let project = Project::current()?;
+        let paths: Vec<String> = match args.values_of("paths") {
+            Some(paths) => paths.map(String::from).collect(),
+            None if project.files.is_empty() => vec![project.path(".").display().to_string()],
+            None => project
+                .files
+                .iter()
+                .map(|file| project.path(file).display().to_string())
+                .collect(),
+        };
+        let paths = Sources::find_excluding(
+            &paths.iter().map(String::as_str).collect::<Vec<_>>(),
+            &project.root,
+            &project.exclude,
+        )?;
+        let jobs = match args.value_of("jobs") {
+            Some(jobs) => jobs.parse().unwrap(),
+            None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+        let mut output = Output {
+            format: Format::from_name(args.value_of("format").unwrap()).unwrap(),
+            diagnostics: Vec::new(),
+            files: 0,
+            failed: 0,
+            single: paths.len() == 1 && !paths[0].extension().is_some_and(|e| e.eq_ignore_ascii_case("pbo")),
+            error: None,
+        };
+        Lint::parallel(&paths, jobs, &project, args.is_present("duplicate-includes"), &mut |linted| {
+            output.add(linted)
+        });
+        output.finish();
+        if let Some(err) = output.error {
+            return Err(err);
+        }
+        if output.failed > 0 {
+            return Err(ArmaLintError::GENERIC(format!(
+                "{} could not be linted",
+                plural(output.failed, "file")
+            )));
+        }
+
+        let max_warnings = if args.is_present("deny-warnings") {
+            Some(0)
+        } else {
+            args.value_of("max-warnings").map(|v| v.parse().unwrap())
+        };
+        let errors = output.errors();
+        let warnings = output.diagnostics.len() - errors;
+        if errors > 0 || max_warnings.is_some_and(|max| warnings > max) {
+            return Err(ArmaLintError::Lint {
+                errors,
+                warnings,
+                max_warnings,
+            });
         }
         Ok(())
     }
 }
+
+fn number(value: String) -> Result<(), String> {
+    value
+        .parse::<usize>()
+        .map(|_| ())
+        .map_err(|_| format!("`{}` is not a number", value))
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use crate::pbo::Pbo;
+use crate::{ArmaLintError, Command};
+
+pub struct List {}
+impl Command for List {
+    fn register(&self) -> clap::App {
+        clap::SubCommand::with_name("list")
+            .version(*crate::VERSION)
+            .about("List the files in a PBO")
+            .arg(clap::Arg::with_name("file").help("PBO to list").required(true))
+    }
+
+    fn run(&self, args: &clap::ArgMatches) -> Result<(), ArmaLintError> {
+        let path = PathBuf::from(args.value_of("file").unwrap());
+        let pbo = Pbo::read(open_file!(path)?)?;
+        for (key, value) in &pbo.extensions {
+            println!("{} = {}", key, value);
+        }
+        let mut size = 0;
+        for file in &pbo.files {
+            println!("{:>10}  {}", file.data.len(), file.name);
+            size += file.data.len();
+        }
+        println!(
+            "{} file{}, {} bytes",
+            pbo.files.len(),
+            if pbo.files.len() == 1 { "" } else { "s" },
+            size
+        );
+        Ok(())
+    }
+}
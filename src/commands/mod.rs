@@ -1,8 +1,32 @@
 use crate::ArmaLintError;
 
+mod derapify;
+pub use derapify::Derapify;
+
+mod fmt;
+pub use fmt::Fmt;
+
 mod lint;
 pub use lint::Lint;
 
+mod list;
+pub use list::List;
+
+mod pack;
+pub use pack::Pack;
+
+mod project;
+pub use project::Project;
+
+mod rapify;
+pub use rapify::Rapify;
+
+mod sources;
+pub use sources::Sources;
+
+mod unpack;
+pub use unpack::Unpack;
+
 pub trait Command {
     // (name, description)
     fn register(&self) -> clap::App;
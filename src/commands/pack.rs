@@ -0,0 +1,148 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::commands::{Project, Rapify};
+use crate::config::FsResolver;
+use crate::pbo::{Pbo, PboFile};
+use crate::{ArmaLintError, Command};
+
+pub struct Pack {}
+impl Pack {
+    /// Builds a PBO from the files in a directory
+    ///
+    /// When `rapify` is set every `config.cpp` is packed as a rapified `config.bin` with the settings
+    /// of `project`, a config with errors stops the packing and the error names the `target` that is
+    /// not written. `prefix` replaces the prefix from `$PBOPREFIX$`, includes are resolved with it.
+    pub fn pack(
+        dir: &Path,
+        includes: &[PathBuf],
+        project: &Project,
+        rapify: bool,
+        prefix: Option<&str>,
+        target: &Path,
+    ) -> Result<Pbo, ArmaLintError> {
+        let mut pbo = Pbo::default();
+        let prefix_file = dir.join(Pbo::PREFIX_FILE);
+        if prefix_file.is_file() {
+            let mut content = String::new();
+            open_file!(prefix_file)?.read_to_string(&mut content)?;
+            pbo.extensions = Pbo::parse_prefix_file(&content);
+        }
+        if let Some(prefix) = prefix {
+            pbo.extensions.retain(|(key, _)| !key.eq_ignore_ascii_case("prefix"));
+            pbo.extensions.insert(0, ("prefix".to_string(), prefix.to_string()));
+        }
+
+        let mut resolver = FsResolver::new();
+        if let Some(prefix) = pbo.extension("prefix") {
+            resolver = resolver.prefix(prefix, dir);
+        }
+        for include in includes {
+            resolver = resolver.prefix_files(include)?.search_path(include);
+        }
+        let resolver = project.with_includes(resolver)?;
+
+        let mut paths = Vec::new();
+        Pack::files(dir, &mut paths)?;
+        for path in paths {
+            let relative = path.strip_prefix(dir).unwrap();
+            if relative == Path::new(Pbo::PREFIX_FILE) {
+                continue;
+            }
+            let mut name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("\\");
+            let file = path.file_name().unwrap().to_string_lossy();
+            let data = if rapify && file.eq_ignore_ascii_case("config.cpp") {
+                name = format!("{}config.bin", &name[..name.len() - file.len()]);
+                let mut data = Vec::new();
+                Rapify::config(&path, &resolver, project, target)?.write_rapified(&mut data)?;
+                data
+            } else {
+                let mut data = Vec::new();
+                open_file!(path)?.read_to_end(&mut data)?;
+                data
+            };
+            let timestamp = std::fs::metadata(&path)?
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |time| time.as_secs() as u32);
+            pbo.files.push(PboFile { name, timestamp, data });
+        }
+        Ok(pbo)
+    }
+
+    /// Every file in a directory sorted by path, hidden files and directories are skipped
+    fn files(dir: &Path, found: &mut Vec<PathBuf>) -> Result<(), ArmaLintError> {
+        let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            if path.is_dir() {
+                Pack::files(&path, found)?;
+            } else {
+                found.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Command for Pack {
+    fn register(&self) -> clap::App {
+        clap::SubCommand::with_name("pack")
+            .version(*crate::VERSION)
+            .about("Pack a directory into a PBO")
+            .arg(clap::Arg::with_name("dir").help("Directory to pack").required(true))
+            .arg(clap::Arg::with_name("output").help("File to write, defaults to the directory with a `.pbo` extension"))
+            .arg(
+                clap::Arg::with_name("prefix")
+                    .help("Prefix of the PBO, replaces the prefix from `$PBOPREFIX$`")
+                    .long("prefix")
+                    .takes_value(true),
+            )
+            .arg(
+                clap::Arg::with_name("no-rapify")
+                    .help("Pack `config.cpp` files as they are")
+                    .long("no-rapify"),
+            )
+            .arg(
+                clap::Arg::with_name("include")
+                    .help("Directory to search for files included by configs")
+                    .short("i")
+                    .long("include")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1),
+            )
+    }
+
+    fn run(&self, args: &clap::ArgMatches) -> Result<(), ArmaLintError> {
+        let dir = PathBuf::from(args.value_of("dir").unwrap());
+        if !dir.is_dir() {
+            return Err(ArmaLintError::InvalidInput(format!("`{}` is not a directory", dir.display())));
+        }
+        let output = match args.value_of("output") {
+            Some(output) => PathBuf::from(output),
+            None => dir.with_extension("pbo"),
+        };
+        let includes: Vec<PathBuf> = args.values_of("include").into_iter().flatten().map(PathBuf::from).collect();
+        let pbo = Pack::pack(
+            &dir,
+            &includes,
+            &Project::current()?,
+            !args.is_present("no-rapify"),
+            args.value_of("prefix"),
+            &output,
+        )?;
+        pbo.write(&mut create_file!(output)?)?;
+        Ok(())
+    }
+}
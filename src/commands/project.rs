@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::config::{Code, Diagnostic, FsResolver, PreProcessor, RenderOptions, Report, Severity};
+use crate::ArmaLintError;
+
+/// Settings of a repository, read from the closest `armalint.toml` or `.armalint.toml`
+///
+/// ```toml
+/// [includes]
+/// paths = ["include"]
+/// prefixes = { 'z\ace\addons\main' = "addons/main" }
+///
+/// [defines]
+/// DEBUG_MODE_FULL = true
+/// VERSION = 3
+/// "QUOTE(var)" = "#var"
+///
+/// [rules]
+/// CFG005 = "off"
+/// CFG006 = "error"
+///
+/// [files]
+/// include = ["addons"]
+/// exclude = ["addons/old", "*.sqm"]
+///
+/// [render]
+/// bracket_style = "linux"
+/// ```
+/// Every section is optional, paths are relative to the directory of the project file.
+#[derive(Clone)]
+pub struct Project {
+    /// The project file, `None` when no project file was found
+    pub file: Option<PathBuf>,
+    /// Directory of the project file
+    pub root: PathBuf,
+    /// Directories searched for included files, their addons are mapped by their `$PBOPREFIX$`
+    pub includes: Vec<PathBuf>,
+    /// Directories of include prefixes such as `z\ace\addons\main`
+    pub prefixes: Vec<(String, PathBuf)>,
+    /// Defined before a config is processed, a define set to `true` has no value
+    pub defines: Vec<(String, Option<String>)>,
+    /// Codes reported with another severity, or not at all when `None`
+    pub rules: HashMap<Code, Option<Severity>>,
+    /// Files, directories and glob patterns that are linted when none are given
+    pub files: Vec<String>,
+    /// Patterns of files that are not linted, written like the lines of an ignore file
+    pub exclude: Vec<String>,
+    pub render: RenderOptions,
+    /// Directory the project file was searched from
+    dir: PathBuf,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IncludesSection {
+    #[serde(default)]
+    paths: Vec<PathBuf>,
+    #[serde(default)]
+    prefixes: BTreeMap<String, PathBuf>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FilesSection {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectFile {
+    #[serde(default)]
+    includes: IncludesSection,
+    #[serde(default)]
+    defines: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    rules: BTreeMap<String, String>,
+    #[serde(default)]
+    files: FilesSection,
+    #[serde(default)]
+    render: RenderOptions,
+}
+
+impl Project {
+    /// Names of the project file, in the order they are looked for in each directory
+    pub const FILES: [&'static str; 2] = ["armalint.toml", ".armalint.toml"];
+
+    /// Loads the project file of the current directory
+    pub fn current() -> Result<Self, ArmaLintError> {
+        Project::load(&std::env::current_dir()?)
+    }
+
+    /// Loads the project file in `dir` or the closest parent directory that has one
+    ///
+    /// The default settings are used when there is no project file.
+    pub fn load(dir: &Path) -> Result<Self, ArmaLintError> {
+        for parent in dir.ancestors() {
+            for name in &Project::FILES {
+                let path = parent.join(name);
+                if path.is_file() {
+                    let mut content = String::new();
+                    open_file!(path)?.read_to_string(&mut content)?;
+                    let mut project = Project::read(&content, parent, &path.display().to_string())?;
+                    project.file = Some(path);
+                    project.dir = dir.to_path_buf();
+                    return Ok(project);
+                }
+            }
+        }
+        Project::from_toml("", dir)
+    }
+
+    /// Reads the settings of a project file in `root`
+    pub fn from_toml(content: &str, root: &Path) -> Result<Self, ArmaLintError> {
+        Project::read(content, root, Project::FILES[0])
+    }
+
+    /// Reads the settings of a project file, errors are prefixed with its `name`
+    fn read(content: &str, root: &Path, name: &str) -> Result<Self, ArmaLintError> {
+        let invalid = |message: String| ArmaLintError::InvalidInput(format!("{}: {}", name, message));
+        let file = toml::from_str::<ProjectFile>(content).map_err(|e| invalid(e.to_string()))?;
+        let mut defines = Vec::new();
+        for (ident, value) in file.defines {
+            match value {
+                toml::Value::Boolean(false) => {}
+                toml::Value::Boolean(true) => defines.push((ident, None)),
+                toml::Value::String(value) => defines.push((ident, Some(value))),
+                toml::Value::Integer(value) => defines.push((ident, Some(value.to_string()))),
+                toml::Value::Float(value) => defines.push((ident, Some(value.to_string()))),
+                other => return Err(invalid(format!("invalid value `{}` for define `{}`", other, ident))),
+            }
+        }
+        let mut rules = HashMap::new();
+        for (name, severity) in file.rules {
+            let code = Code::from_name(&name).ok_or_else(|| invalid(format!("unknown rule `{}`", name)))?;
+            let severity = match severity.as_str() {
+                "off" => None,
+                "warning" => Some(Severity::Warning),
+                "error" => Some(Severity::Error),
+                _ => return Err(invalid(format!("invalid severity `{}` for `{}`", severity, name))),
+            };
+            rules.insert(code, severity);
+        }
+        Ok(Project {
+            file: None,
+            root: root.to_path_buf(),
+            includes: file.includes.paths.iter().map(|path| root.join(path)).collect(),
+            prefixes: file
+                .includes
+                .prefixes
+                .into_iter()
+                .map(|(prefix, path)| (prefix, root.join(path)))
+                .collect(),
+            defines,
+            rules,
+            files: file.files.include,
+            exclude: file.files.exclude,
+            render: file.render,
+            dir: root.to_path_buf(),
+        })
+    }
+
+    /// A path of the project file, relative to the directory it was searched from when it can be
+    pub fn path(&self, path: &str) -> PathBuf {
+        let path = self.root.join(path);
+        match path.strip_prefix(&self.dir) {
+            Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path,
+        }
+    }
+
+    /// Adds the include paths and prefixes of the project to a resolver
+    pub fn with_includes(&self, mut resolver: FsResolver) -> Result<FsResolver, ArmaLintError> {
+        for (prefix, dir) in &self.prefixes {
+            resolver = resolver.prefix(prefix, dir);
+        }
+        for dir in &self.includes {
+            resolver = resolver.prefix_files(dir)?.search_path(dir);
+        }
+        Ok(resolver)
+    }
+
+    /// A preprocessor with the defines of the project
+    pub fn preprocessor(&self) -> Result<PreProcessor, ArmaLintError> {
+        let mut preprocessor = PreProcessor::new();
+        for (ident, value) in &self.defines {
+            preprocessor = preprocessor.define(ident, value.as_deref())?;
+        }
+        Ok(preprocessor)
+    }
+
+    /// Applies the rules of the project to diagnostics, leaving out the codes that are turned off
+    pub fn report<I: IntoIterator<Item = Diagnostic>>(&self, diagnostics: I) -> Report {
+        let mut report = Report::new();
+        for mut diagnostic in diagnostics {
+            match self.rules.get(&diagnostic.code) {
+                Some(None) => continue,
+                Some(Some(severity)) => diagnostic.severity = *severity,
+                None => {}
+            }
+            report.push(diagnostic);
+        }
+        report
+    }
+}
+
+#[test]
+fn rules() {
+    let project = Project::from_toml("[rules]\ncfg006 = \"error\"\nCFG007 = \"off\"", Path::new(".")).unwrap();
+    let span = crate::config::Span {
+        file: "config.cpp".to_string(),
+        start: (1, 1),
+        end: (1, 2),
+    };
+    let report = project.report(vec![
+        Diagnostic::new(Code::NonUppercaseDefine, "lowercase", span.clone()),
+        Diagnostic::new(Code::Redefine, "redefined", span.clone()),
+        Diagnostic::new(Code::UnquotedValue, "unquoted", span),
+    ]);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].code, Code::NonUppercaseDefine);
+    assert_eq!(report.warnings.len(), 1);
+    assert!(Project::from_toml("[rules]\nCFG999 = \"off\"", Path::new(".")).is_err());
+}
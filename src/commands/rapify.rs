@@ -0,0 +1,105 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::commands::Project;
+use crate::config::simplify::Config;
+use crate::config::{FsResolver, IncludeResolver};
+use crate::{ArmaLintError, Command};
+
+pub struct Rapify {}
+impl Rapify {
+    /// Resolver for the includes of a config
+    ///
+    /// The addon of the config and every addon in the search paths are mapped by their `$PBOPREFIX$`.
+    pub fn resolver(path: &Path, includes: &[PathBuf]) -> Result<FsResolver, ArmaLintError> {
+        let mut resolver = FsResolver::new().addon_of(path)?;
+        for dir in includes {
+            resolver = resolver.prefix_files(dir)?.search_path(dir);
+        }
+        Ok(resolver)
+    }
+
+    /// Preprocesses and simplifies a config with the defines and rules of a project, printing its
+    /// warnings and errors
+    ///
+    /// A config with errors is not simplified, the error names the `target` that is not written.
+    pub fn config(
+        path: &Path,
+        resolver: &dyn IncludeResolver,
+        project: &Project,
+        target: &Path,
+    ) -> Result<Config, ArmaLintError> {
+        let wd = path.parent().map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        let mut content = String::new();
+        open_file!(path)?.read_to_string(&mut content)?;
+        let ast = match crate::config::parse_with_resolver(&path.display().to_string(), wd, &content, resolver) {
+            Err(ArmaLintError::SyntaxErrors(ast)) => {
+                let count = ast.report.as_ref().unwrap().errors.len();
+                ArmaLintError::SyntaxErrors(ast).error();
+                return Err(Rapify::not_writing(target, count));
+            }
+            result => result?,
+        };
+        let processed = project.preprocessor()?.process(ast)?;
+        // The rules of the project only change how diagnostics are reported, any error of the
+        // preprocessor stops the config from being written
+        let errors = processed.report.as_ref().map_or(0, |report| report.errors.len());
+        let report = processed.report.clone().unwrap();
+        let report = project.report(report.errors.into_iter().chain(report.warnings));
+        for diagnostic in report.warnings.iter().chain(&report.errors) {
+            diagnostic!(processed.files, diagnostic);
+        }
+        if !processed.valid() {
+            return Err(Rapify::not_writing(target, errors));
+        }
+        Config::from_ast(processed)
+    }
+
+    fn not_writing(target: &Path, count: usize) -> ArmaLintError {
+        ArmaLintError::GENERIC(format!(
+            "Not writing `{}`, the config has {} error{}",
+            target.display(),
+            count,
+            if count == 1 { "" } else { "s" }
+        ))
+    }
+}
+
+impl Command for Rapify {
+    fn register(&self) -> clap::App {
+        clap::SubCommand::with_name("rapify")
+            .version(*crate::VERSION)
+            .about("Rapify a config")
+            .arg(clap::Arg::with_name("file").help("Config to rapify").required(true))
+            .arg(clap::Arg::with_name("output").help("File to write, defaults to the input with a `.bin` extension"))
+            .arg(
+                clap::Arg::with_name("include")
+                    .help("Directory to search for included files")
+                    .short("i")
+                    .long("include")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1),
+            )
+    }
+
+    fn run(&self, args: &clap::ArgMatches) -> Result<(), ArmaLintError> {
+        let path = PathBuf::from(args.value_of("file").unwrap());
+        let output = match args.value_of("output") {
+            Some(output) => PathBuf::from(output),
+            None => path.with_extension("bin"),
+        };
+        if output == path {
+            return Err(ArmaLintError::InvalidInput(format!(
+                "Refusing to overwrite the input `{}`",
+                path.display()
+            )));
+        }
+        let includes: Vec<PathBuf> = args.values_of("include").into_iter().flatten().map(PathBuf::from).collect();
+        let project = Project::current()?;
+        let resolver = project.with_includes(Rapify::resolver(&path, &includes)?)?;
+        let config = Rapify::config(&path, &resolver, &project, &output)?;
+        config.write_rapified(&mut create_file!(output)?)?;
+        Ok(())
+    }
+}
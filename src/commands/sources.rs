@@ -0,0 +1,311 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::ArmaLintError;
+
+/// Finds the files to lint from paths, directories and glob patterns
+///
+/// Directories are searched for config sources, skipping hidden files and anything matched by an
+/// ignore file. Ignore files use the patterns of `.gitignore`, they apply to the directory they are in
+/// and the directories of a repository above it.
+pub struct Sources {}
+impl Sources {
+    /// Files with patterns of paths that are not linted
+    pub const IGNORE_FILES: [&'static str; 2] = [".gitignore", ".armalintignore"];
+
+    /// Extensions of the files that can be linted when they are named directly or by a glob pattern
+    pub const EXTENSIONS: [&'static str; 6] = ["cpp", "hpp", "ext", "sqm", "bin", "pbo"];
+
+    /// Whether a file found in a directory is a config source
+    pub fn is_source(path: &Path) -> bool {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+        name == "config.cpp" || name == "description.ext" || name.ends_with(".hpp") || name.ends_with(".sqm")
+    }
+
+    /// Whether a file can be linted
+    pub fn is_supported(path: &Path) -> bool {
+        path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| Sources::EXTENSIONS.contains(&e.as_str()))
+    }
+
+    /// Every file to lint in the order the paths are given, without duplicates
+    ///
+    /// Files named directly are always linted, a glob pattern such as `addons/*/config.cpp` or
+    /// `addons/**/*.hpp` has to match at least one file.
+    pub fn find(paths: &[&str]) -> Result<Vec<PathBuf>, ArmaLintError> {
+        Sources::find_excluding(paths, Path::new("."), &[])
+    }
+
+    /// Every file to lint, leaving out the files found in directories or by glob patterns that match
+    /// one of the `exclude` patterns
+    ///
+    /// The patterns are written like the lines of an ignore file in `root`.
+    pub fn find_excluding(paths: &[&str], root: &Path, exclude: &[String]) -> Result<Vec<PathBuf>, ArmaLintError> {
+        let root = root.canonicalize()?;
+        let exclude: Vec<Rule> = exclude.iter().filter_map(|line| Rule::parse(&root, line)).collect();
+        let mut found = Vec::new();
+        for path in paths {
+            let mut files = Vec::new();
+            if is_glob(path) {
+                let (base, pattern) = split_glob(path);
+                let pattern = Regex::new(&format!("^{}$", glob_regex(&pattern)))
+                    .map_err(|e| ArmaLintError::InvalidInput(format!("Invalid glob pattern `{}`: {}", path, e)))?;
+                walk(&base, &exclude, &mut |file, relative| {
+                    if pattern.is_match(relative) && Sources::is_supported(file) {
+                        files.push(file.to_path_buf());
+                    }
+                })?;
+                if files.is_empty() {
+                    return Err(ArmaLintError::InvalidInput(format!("No files match `{}`", path)));
+                }
+            } else {
+                let path = PathBuf::from(path);
+                if path.is_dir() {
+                    walk(&path, &exclude, &mut |file, _| {
+                        if Sources::is_source(file) {
+                            files.push(file.to_path_buf());
+                        }
+                    })?;
+                } else if path.is_file() {
+                    files.push(path);
+                } else {
+                    return Err(ArmaLintError::InvalidInput(format!("`{}` does not exist", path.display())));
+                }
+            }
+            for file in files {
+                if !found.contains(&file) {
+                    found.push(file);
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// A line of an ignore file
+#[derive(Clone)]
+struct Rule {
+    /// Directory of the ignore file
+    base: PathBuf,
+    pattern: Regex,
+    negate: bool,
+    dir_only: bool,
+}
+
+impl Rule {
+    fn parse(base: &Path, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negate, line) = match line.strip_prefix('!') {
+            Some(line) => (true, line),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        // A pattern with a `/` before its end is relative to the ignore file, otherwise it matches at any depth
+        let pattern = if line.contains('/') {
+            format!("^{}$", glob_regex(line.trim_start_matches('/')))
+        } else {
+            format!("^(?:.*/)?{}$", glob_regex(line))
+        };
+        Some(Rule {
+            base: base.to_path_buf(),
+            pattern: Regex::new(&pattern).ok()?,
+            negate,
+            dir_only,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        match path.strip_prefix(&self.base) {
+            Ok(relative) => self.pattern.is_match(&slashed(relative)),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Rules of the ignore files in a directory
+fn rules(dir: &Path) -> Result<Vec<Rule>, ArmaLintError> {
+    let mut rules = Vec::new();
+    for name in &Sources::IGNORE_FILES {
+        let file = dir.join(name);
+        if file.is_file() {
+            let mut content = String::new();
+            open_file!(file)?.read_to_string(&mut content)?;
+            rules.extend(content.lines().filter_map(|line| Rule::parse(dir, line)));
+        }
+    }
+    Ok(rules)
+}
+
+/// Whether a path is ignored, the last rule that matches wins
+fn ignored(rules: &[Rule], path: &Path, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(path, is_dir))
+        .is_some_and(|rule| !rule.negate)
+}
+
+/// Calls `visit` with every file in a directory that is not hidden or ignored, sorted by path
+///
+/// `visit` also gets the path of the file relative to `root` with `/` separators. `exclude` is used
+/// before the rules of any ignore file.
+fn walk(root: &Path, exclude: &[Rule], visit: &mut dyn FnMut(&Path, &str)) -> Result<(), ArmaLintError> {
+    let canonical = root.canonicalize()?;
+    // Ignore files above the root apply up to the root of the repository
+    let mut inherited = Vec::new();
+    for dir in canonical.ancestors().skip(1) {
+        inherited.splice(0..0, rules(dir)?);
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    inherited.splice(0..0, exclude.iter().cloned());
+    walk_dir(root, &canonical, &canonical, inherited, visit)
+}
+
+fn walk_dir(
+    dir: &Path,
+    canonical: &Path,
+    root: &Path,
+    mut rules: Vec<Rule>,
+    visit: &mut dyn FnMut(&Path, &str),
+) -> Result<(), ArmaLintError> {
+    rules.extend(self::rules(canonical)?);
+    let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let canonical = canonical.join(&name);
+        let is_dir = path.is_dir();
+        if ignored(&rules, &canonical, is_dir) {
+            continue;
+        }
+        if is_dir {
+            walk_dir(&path, &canonical, root, rules.clone(), visit)?;
+        } else {
+            visit(&path, &slashed(canonical.strip_prefix(root).unwrap()));
+        }
+    }
+    Ok(())
+}
+
+/// A relative path with `/` separators
+fn slashed(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Splits a glob into the directory before the first part with a wildcard and the rest of the pattern
+fn split_glob(path: &str) -> (PathBuf, String) {
+    let parts: Vec<&str> = path.split(['/', '\\']).collect();
+    let first = parts.iter().position(|part| is_glob(part)).unwrap_or(0);
+    let base = parts[..first].join("/");
+    let base = if base.is_empty() && path.starts_with('/') {
+        PathBuf::from("/")
+    } else if base.is_empty() {
+        PathBuf::from(".")
+    } else {
+        PathBuf::from(base)
+    };
+    (base, parts[first..].join("/"))
+}
+
+/// Regex of a glob, `*` and `?` don't match `/` while `**` matches any number of directories
+fn glob_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let chars: Vec<char> = glob.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    regex.push_str("(?:.*/)?");
+                    i += 1;
+                } else {
+                    regex.push_str(".*");
+                }
+                i += 1;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => match chars[i..].iter().position(|c| *c == ']') {
+                Some(end) if end > 1 => {
+                    regex.push_str(&glob_class(&chars[i + 1..i + end]));
+                    i += end;
+                }
+                _ => regex.push_str("\\["),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    regex
+}
+
+/// Regex of the body of a `[...]` class, `!` at the start negates it
+///
+/// Characters the regex crate gives a meaning inside classes are escaped, so `[[]` matches `[` and
+/// `&&`, `--` and `~~` are not set operations.
+fn glob_class(body: &[char]) -> String {
+    let (negate, body) = match body.split_first() {
+        Some(('!', rest)) if !rest.is_empty() => (true, rest),
+        _ => (false, body),
+    };
+    let mut class = String::from(if negate { "[^" } else { "[" });
+    for (i, c) in body.iter().enumerate() {
+        let doubled = |c: char| body.get(i + 1) == Some(&c) || (i > 0 && body[i - 1] == c);
+        match c {
+            '\\' | '[' | '&' | '~' => class.push('\\'),
+            '^' if i == 0 && !negate => class.push('\\'),
+            '-' if i == 0 || i == body.len() - 1 || doubled('-') => class.push('\\'),
+            _ => {}
+        }
+        class.push(*c);
+    }
+    class.push(']');
+    class
+}
+
+#[test]
+fn globs() {
+    let matches = |glob: &str, path: &str| Regex::new(&format!("^{}$", glob_regex(glob))).unwrap().is_match(path);
+    assert!(matches("*/config.cpp", "main/config.cpp"));
+    assert!(!matches("*/config.cpp", "main/sub/config.cpp"));
+    assert!(matches("**/*.hpp", "macros.hpp"));
+    assert!(matches("**/*.hpp", "main/ui/dialog.hpp"));
+    assert!(matches("main/**", "main/ui/dialog.hpp"));
+    assert!(matches("c?nfig.[ch]pp", "config.hpp"));
+    assert!(!matches("config.[!ch]pp", "config.cpp"));
+    assert!(matches("x[[]y.cpp", "x[y.cpp"));
+    assert!(matches("[a&&b].cpp", "&.cpp"));
+    assert!(matches("[a--z].cpp", "-.cpp"));
+    assert!(matches("[~~].cpp", "~.cpp"));
+    assert!(matches("[^a].cpp", "^.cpp"));
+    assert!(matches("[a-c].cpp", "b.cpp"));
+    assert_eq!(
+        split_glob("addons/*/config.cpp"),
+        (PathBuf::from("addons"), "*/config.cpp".to_string())
+    );
+}
@@ -0,0 +1,75 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::PboError;
+use crate::pbo::Pbo;
+use crate::{ArmaLintError, Command};
+
+pub struct Unpack {}
+impl Unpack {
+    /// Path of a file relative to the directory it is unpacked to
+    ///
+    /// Names that are absolute or go up a directory are refused.
+    pub fn path(name: &str) -> Result<PathBuf, ArmaLintError> {
+        let parts: Vec<&str> = name.split(['\\', '/']).collect();
+        if parts[0].is_empty() || parts.iter().any(|p| *p == ".." || p.contains(':')) {
+            return Err(PboError::InvalidPath(name.to_string()).into());
+        }
+        Ok(parts.iter().filter(|p| !p.is_empty() && **p != ".").collect())
+    }
+
+    /// Writes the files of a PBO to a directory, the header extensions are written to `$PBOPREFIX$`
+    pub fn unpack(pbo: &Pbo, dir: &Path) -> Result<(), ArmaLintError> {
+        let paths = pbo
+            .files
+            .iter()
+            .map(|file| Unpack::path(&file.name))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (file, path) in pbo.files.iter().zip(paths) {
+            let path = dir.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            create_file!(path)?.write_all(&file.data)?;
+        }
+        if !pbo.extensions.is_empty() && pbo.file(Pbo::PREFIX_FILE).is_none() {
+            let mut content = String::new();
+            for (key, value) in &pbo.extensions {
+                if key == "prefix" {
+                    content.insert_str(0, &format!("{}\n", value));
+                } else {
+                    content.push_str(&format!("{}={}\n", key, value));
+                }
+            }
+            std::fs::create_dir_all(dir)?;
+            create_file!(dir.join(Pbo::PREFIX_FILE))?.write_all(content.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl Command for Unpack {
+    fn register(&self) -> clap::App {
+        clap::SubCommand::with_name("unpack")
+            .version(*crate::VERSION)
+            .about("Unpack a PBO")
+            .arg(clap::Arg::with_name("file").help("PBO to unpack").required(true))
+            .arg(clap::Arg::with_name("output").help("Directory to write to, defaults to the PBO without its extension"))
+    }
+
+    fn run(&self, args: &clap::ArgMatches) -> Result<(), ArmaLintError> {
+        let path = PathBuf::from(args.value_of("file").unwrap());
+        let output = match args.value_of("output") {
+            Some(output) => PathBuf::from(output),
+            None => path.with_extension(""),
+        };
+        if output == path {
+            return Err(ArmaLintError::InvalidInput(format!(
+                "Refusing to overwrite the input `{}`",
+                path.display()
+            )));
+        }
+        let pbo = Pbo::read(open_file!(path)?)?;
+        Unpack::unpack(&pbo, &output)
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+
+use super::{Node, Statement};
+
+/// Result of evaluating the condition of an `#if` or `#elif`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub value: i64,
+    /// Identifiers that were not defined and evaluated to `0`
+    pub undefined: Vec<String>,
+    /// The result does not depend on any define
+    pub constant: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Ident(String),
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 24] = [
+    "&&", "||", "==", "!=", "<=", ">=", "<<", ">>", "<", ">", "+", "-", "*", "/", "%", "!", "~", "&", "|", "^", "(", ")",
+    "?", ":",
+];
+
+const MAX_DEPTH: u8 = 32;
+
+/// Evaluates a preprocessor constant expression
+///
+/// Defines are expanded using the current `defines` table, `defined(X)` and `defined X` check both defines and macros.
+/// Identifiers that are not defined evaluate to `0` and are returned in `Evaluation::undefined`.
+pub fn evaluate(
+    condition: &str,
+    defines: &HashMap<String, Option<Node>>,
+    macros: &HashMap<String, (Vec<String>, Node)>,
+) -> Result<Evaluation, String> {
+    let mut evaluator = Evaluator {
+        tokens: tokenize(&strip_comments(condition))?,
+        position: 0,
+        defines,
+        macros,
+        undefined: Vec::new(),
+        constant: true,
+        depth: 0,
+    };
+    let value = evaluator.expression()?;
+    Ok(Evaluation {
+        value,
+        undefined: evaluator.undefined,
+        constant: evaluator.constant,
+    })
+}
+
+fn strip_comments(condition: &str) -> String {
+    let mut output = String::new();
+    let mut rest = condition.replace("\\\n", " ");
+    while let Some(start) = rest.find('/') {
+        if rest[start..].starts_with("//") {
+            rest.truncate(start);
+        } else if rest[start..].starts_with("/*") {
+            output.push_str(&rest[..start]);
+            output.push(' ');
+            rest = match rest[start + 2..].find("*/") {
+                Some(end) => rest[start + 2 + end + 2..].to_string(),
+                None => String::new(),
+            };
+        } else {
+            output.push_str(&rest[..=start]);
+            rest = rest[start + 1..].to_string();
+        }
+    }
+    output.push_str(&rest);
+    output
+}
+
+fn tokenize(condition: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = condition.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let digits = literal.trim_end_matches(&['u', 'U', 'l', 'L'][..]);
+            let value = if digits.starts_with("0x") || digits.starts_with("0X") {
+                i64::from_str_radix(&digits[2..], 16)
+            } else {
+                digits.parse()
+            };
+            tokens.push(Token::Number(
+                value.map_err(|_| format!("Invalid number `{}` in condition", literal))?,
+            ));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..].iter().take(2).collect();
+            match OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+                Some(op) => {
+                    tokens.push(Token::Op(op));
+                    i += op.len();
+                }
+                None => return Err(format!("Unexpected `{}` in condition", c)),
+            }
+        }
+    }
+    if tokens.is_empty() {
+        return Err("Empty condition".to_string());
+    }
+    Ok(tokens)
+}
+
+struct Evaluator<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    defines: &'a HashMap<String, Option<Node>>,
+    macros: &'a HashMap<String, (Vec<String>, Node)>,
+    undefined: Vec<String>,
+    constant: bool,
+    depth: u8,
+}
+
+impl<'a> Evaluator<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn accept(&mut self, op: &str) -> bool {
+        match self.peek() {
+            Some(Token::Op(o)) if *o == op => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        if self.accept(op) {
+            Ok(())
+        } else {
+            Err(format!("Expected `{}` in condition", op))
+        }
+    }
+
+    fn expression(&mut self) -> Result<i64, String> {
+        let value = self.ternary()?;
+        if let Some(token) = self.peek() {
+            return Err(format!("Unexpected {} in condition", describe(token)));
+        }
+        Ok(value)
+    }
+
+    fn ternary(&mut self) -> Result<i64, String> {
+        let condition = self.binary(0)?;
+        if self.accept("?") {
+            let positive = self.ternary()?;
+            self.expect(":")?;
+            let negative = self.ternary()?;
+            Ok(if condition != 0 { positive } else { negative })
+        } else {
+            Ok(condition)
+        }
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Result<i64, String> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            let precedence = match precedence(op) {
+                Some(p) if p >= min_precedence => p,
+                _ => break,
+            };
+            self.position += 1;
+            let right = self.binary(precedence + 1)?;
+            left = match op {
+                "||" => ((left != 0) || (right != 0)) as i64,
+                "&&" => ((left != 0) && (right != 0)) as i64,
+                "|" => left | right,
+                "^" => left ^ right,
+                "&" => left & right,
+                "==" => (left == right) as i64,
+                "!=" => (left != right) as i64,
+                "<" => (left < right) as i64,
+                ">" => (left > right) as i64,
+                "<=" => (left <= right) as i64,
+                ">=" => (left >= right) as i64,
+                "<<" => left.wrapping_shl(right as u32),
+                ">>" => left.wrapping_shr(right as u32),
+                "+" => left.wrapping_add(right),
+                "-" => left.wrapping_sub(right),
+                "*" => left.wrapping_mul(right),
+                "/" | "%" if right == 0 => return Err("Division by zero in condition".to_string()),
+                "/" => left.wrapping_div(right),
+                "%" => left.wrapping_rem(right),
+                _ => unreachable!(),
+            };
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<i64, String> {
+        if self.accept("!") {
+            Ok((self.unary()? == 0) as i64)
+        } else if self.accept("-") {
+            Ok(self.unary()?.wrapping_neg())
+        } else if self.accept("+") {
+            self.unary()
+        } else if self.accept("~") {
+            Ok(!self.unary()?)
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<i64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Op("(")) => {
+                let value = self.ternary()?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            Some(Token::Ident(ref ident)) if ident == "defined" => {
+                self.constant = false;
+                let parens = self.accept("(");
+                let ident = match self.next() {
+                    Some(Token::Ident(ident)) => ident,
+                    _ => return Err("Expected identifier after `defined`".to_string()),
+                };
+                if parens {
+                    self.expect(")")?;
+                }
+                Ok((self.defines.contains_key(&ident) || self.macros.contains_key(&ident)) as i64)
+            }
+            Some(Token::Ident(ident)) => {
+                self.constant = false;
+                self.ident(ident)
+            }
+            Some(token) => Err(format!("Unexpected {} in condition", describe(&token))),
+            None => Err("Unexpected end of condition".to_string()),
+        }
+    }
+
+    fn ident(&mut self, ident: String) -> Result<i64, String> {
+        if let Some(define) = self.defines.get(&ident) {
+            match define {
+                Some(node) => self.statement(&node.statement),
+                None => Err(format!("Attempt to use flag `{}` in condition", ident)),
+            }
+        } else if self.macros.contains_key(&ident) {
+            Err(format!("Macro `{}` can not be used in a condition", ident))
+        } else {
+            if !self.undefined.contains(&ident) {
+                self.undefined.push(ident);
+            }
+            Ok(0)
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<i64, String> {
+        match statement {
+            Statement::Integer(value) => Ok(i64::from(*value)),
+            Statement::Bool(value) => Ok(*value as i64),
+            Statement::Processed(inner, _) => self.statement(inner),
+            Statement::Defined(node, _) => self.statement(&node.statement),
+            Statement::InternalStr(text) => {
+                if self.depth >= MAX_DEPTH {
+                    return Err("Recursive define in condition".to_string());
+                }
+                let mut nested = Evaluator {
+                    tokens: tokenize(text)?,
+                    position: 0,
+                    defines: self.defines,
+                    macros: self.macros,
+                    undefined: Vec::new(),
+                    constant: true,
+                    depth: self.depth + 1,
+                };
+                let value = nested.expression()?;
+                for ident in nested.undefined {
+                    if !self.undefined.contains(&ident) {
+                        self.undefined.push(ident);
+                    }
+                }
+                Ok(value)
+            }
+            Statement::Float(value) => Err(format!("Non-integer value `{}` in condition", value)),
+            Statement::Str(value) => Err(format!("String value `\"{}\"` in condition", value)),
+            _ => Err(format!("Invalid value `{}` in condition", {
+                use strum::AsStaticRef;
+                statement.as_static()
+            })),
+        }
+    }
+}
+
+fn precedence(op: &str) -> Option<u8> {
+    Some(match op {
+        "||" => 0,
+        "&&" => 1,
+        "|" => 2,
+        "^" => 3,
+        "&" => 4,
+        "==" | "!=" => 5,
+        "<" | ">" | "<=" | ">=" => 6,
+        "<<" | ">>" => 7,
+        "+" | "-" => 8,
+        "*" | "/" | "%" => 9,
+        _ => return None,
+    })
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format!("`{}`", n),
+        Token::Ident(i) => format!("`{}`", i),
+        Token::Op(o) => format!("`{}`", o),
+    }
+}
+
+#[test]
+fn arithmetic() {
+    let defines = HashMap::new();
+    let macros = HashMap::new();
+    assert_eq!(evaluate("1 + 2 * 3 == 7", &defines, &macros).unwrap().value, 1);
+    assert_eq!(evaluate("(1 + 2) * 3", &defines, &macros).unwrap().value, 9);
+    assert_eq!(evaluate("!0 && (2 > 1 || 0)", &defines, &macros).unwrap().value, 1);
+    assert_eq!(evaluate("0x10 >> 2 // comment", &defines, &macros).unwrap().value, 4);
+    assert_eq!(evaluate("1 ? 2 : 3", &defines, &macros).unwrap().value, 2);
+    assert!(evaluate("1 +", &defines, &macros).is_err());
+    assert!(evaluate("1 / 0", &defines, &macros).is_err());
+}
+
+#[test]
+fn identifiers() {
+    let node = |statement| Node {
+        file: String::new(),
+        start: (0, (1, 1)),
+        end: (0, (1, 1)),
+        line: String::new(),
+        statement,
+    };
+    let mut defines = HashMap::new();
+    defines.insert("VERSION".to_string(), Some(node(Statement::Integer(3))));
+    defines.insert(
+        "NEXT".to_string(),
+        Some(node(Statement::Processed(
+            Box::new(Statement::InternalStr("VERSION + 1".to_string())),
+            Box::new(Statement::Gone),
+        ))),
+    );
+    defines.insert("FLAG".to_string(), None);
+    let macros = HashMap::new();
+
+    let eval = evaluate("defined(FLAG) && NEXT == 4", &defines, &macros).unwrap();
+    assert_eq!(eval.value, 1);
+    assert!(!eval.constant);
+    let eval = evaluate("MISSING || defined MISSING", &defines, &macros).unwrap();
+    assert_eq!(eval.value, 0);
+    assert_eq!(eval.undefined, vec!["MISSING".to_string()]);
+    assert!(evaluate("FLAG", &defines, &macros).is_err());
+}
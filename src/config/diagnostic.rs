@@ -0,0 +1,267 @@
+use super::{Files, Node};
+
+/// How serious a diagnostic is, errors stop a config from being rapified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Stable code of each kind of diagnostic, a code keeps its meaning between versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Code {
+    /// `CFG001` The file could not be parsed
+    SyntaxError,
+    /// `CFG002` A macro is called but never defined
+    UndefinedMacro,
+    /// `CFG003` A macro is called with the wrong number of arguments
+    InvalidCall,
+    /// `CFG004` The condition of an `#if` can't be evaluated
+    InvalidCondition,
+    /// `CFG005` A value that should be quoted
+    UnquotedValue,
+    /// `CFG006` A define with lowercase characters in its name
+    NonUppercaseDefine,
+    /// `CFG007` A define or macro that is defined again
+    Redefine,
+    /// `CFG008` `#undef` of something that is not defined
+    UndefineUndefined,
+    /// `CFG009` An undefined identifier in the condition of an `#if`
+    UndefinedInCondition,
+    /// `CFG010` A branch of an `#if` that can never be used
+    Unreachable,
+    /// `CFG011` `__EVAL` or `__EXEC` code that can't be evaluated
+    InvalidEval,
+    /// `CFG012` A file included more than once without an include guard
+    DuplicateInclude,
+    /// `CFG013` Classes of a rapified config that inherit from each other
+    CircularInheritance,
+    /// `CFG014` A class of a rapified config with a parent that doesn't exist
+    MissingParent,
+}
+
+impl Code {
+    /// Every code, in order
+    pub const ALL: [Code; 14] = [
+        Code::SyntaxError,
+        Code::UndefinedMacro,
+        Code::InvalidCall,
+        Code::InvalidCondition,
+        Code::UnquotedValue,
+        Code::NonUppercaseDefine,
+        Code::Redefine,
+        Code::UndefineUndefined,
+        Code::UndefinedInCondition,
+        Code::Unreachable,
+        Code::InvalidEval,
+        Code::DuplicateInclude,
+        Code::CircularInheritance,
+        Code::MissingParent,
+    ];
+
+    /// The code with a name such as `CFG001`, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Code::ALL
+            .iter()
+            .copied()
+            .find(|code| code.as_str().eq_ignore_ascii_case(name))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Code::SyntaxError => "CFG001",
+            Code::UndefinedMacro => "CFG002",
+            Code::InvalidCall => "CFG003",
+            Code::InvalidCondition => "CFG004",
+            Code::UnquotedValue => "CFG005",
+            Code::NonUppercaseDefine => "CFG006",
+            Code::Redefine => "CFG007",
+            Code::UndefineUndefined => "CFG008",
+            Code::UndefinedInCondition => "CFG009",
+            Code::Unreachable => "CFG010",
+            Code::InvalidEval => "CFG011",
+            Code::DuplicateInclude => "CFG012",
+            Code::CircularInheritance => "CFG013",
+            Code::MissingParent => "CFG014",
+        }
+    }
+
+    /// Short description of the problem
+    pub fn description(self) -> &'static str {
+        match self {
+            Code::SyntaxError => "Syntax error",
+            Code::UndefinedMacro => "Call to an undefined macro",
+            Code::InvalidCall => "Macro called with the wrong number of arguments",
+            Code::InvalidCondition => "Condition of an `#if` that can't be evaluated",
+            Code::UnquotedValue => "Unquoted value",
+            Code::NonUppercaseDefine => "Define with lowercase characters",
+            Code::Redefine => "Redefined define or macro",
+            Code::UndefineUndefined => "`#undef` of an undefined identifier",
+            Code::UndefinedInCondition => "Undefined identifier in an `#if` condition",
+            Code::Unreachable => "Unreachable `#if` branch",
+            Code::InvalidEval => "`__EVAL` or `__EXEC` that can't be evaluated",
+            Code::DuplicateInclude => "File included more than once without an include guard",
+            Code::CircularInheritance => "Circular inheritance",
+            Code::MissingParent => "Missing parent class",
+        }
+    }
+
+    /// Severity the code is reported with
+    pub fn severity(self) -> Severity {
+        match self {
+            Code::SyntaxError
+            | Code::UndefinedMacro
+            | Code::InvalidCall
+            | Code::InvalidCondition
+            | Code::CircularInheritance
+            | Code::MissingParent => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl std::fmt::Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A range of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub file: String,
+    /// Line and column of the first character, both start at 1
+    pub start: (usize, usize),
+    /// Line and column after the last character
+    pub end: (usize, usize),
+}
+
+impl Span {
+    /// `len` characters of the first line of a node, starting `offset` characters after the node
+    pub fn in_node(node: &Node, offset: usize, len: usize) -> Self {
+        let (line, col) = node.start.1;
+        Span {
+            file: node.file.clone(),
+            start: (line, col + offset),
+            end: (line, col + offset + len),
+        }
+    }
+
+    /// The whole node
+    pub fn of_node(node: &Node) -> Self {
+        Span {
+            file: node.file.clone(),
+            start: node.start.1,
+            end: node.end.1,
+        }
+    }
+
+    /// Moves a span inside a macro body to the file the macro was defined in
+    fn locate(&mut self, files: &Files) {
+        if let Some((Some((file, offset)), _)) = files.get(&self.file) {
+            self.file = file.clone();
+            self.start.0 += offset - 1;
+            self.end.0 += offset - 1;
+        }
+    }
+}
+
+/// A secondary span with a message, such as where something was first defined
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A suggested change, the text of the span is replaced
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// A problem found in a config
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub code: Code,
+    pub severity: Severity,
+    pub message: String,
+    /// Where the problem is
+    pub span: Span,
+    pub labels: Vec<Label>,
+    pub help: Option<String>,
+    pub note: Option<String>,
+    pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+    /// A diagnostic with the severity of its code
+    pub fn new<S: Into<String>>(code: Code, message: S, span: Span) -> Self {
+        Diagnostic {
+            code,
+            severity: code.severity(),
+            message: message.into(),
+            span,
+            labels: Vec::new(),
+            help: None,
+            note: None,
+            fix: None,
+        }
+    }
+
+    pub fn help<S: Into<String>>(mut self, help: S) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn note<S: Into<String>>(mut self, note: S) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    pub fn label<S: Into<String>>(mut self, span: Span, message: S) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn fix<S: Into<String>>(mut self, span: Span, replacement: S) -> Self {
+        self.fix = Some(Fix {
+            span,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
+    /// Moves every span inside a macro body to the file the macro was defined in
+    pub(crate) fn locate(&mut self, files: &Files) {
+        self.span.locate(files);
+        for label in &mut self.labels {
+            label.span.locate(files);
+        }
+        if let Some(fix) = &mut self.fix {
+            fix.span.locate(files);
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}[{}]: {}",
+            self.span.file, self.span.start.0, self.span.start.1, self.severity, self.code, self.message
+        )
+    }
+}
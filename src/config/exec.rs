@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+
+use super::Statement;
+
+/// A value produced by an `__EVAL` or stored by an `__EXEC`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+}
+
+impl Value {
+    /// Converts the value into a statement that can be simplified
+    pub fn into_statement(self) -> Statement {
+        match self {
+            Value::Number(n) => {
+                if n.fract() == 0.0 && n >= f64::from(i32::MIN) && n <= f64::from(i32::MAX) {
+                    Statement::Integer(n as i32)
+                } else {
+                    Statement::Float(n as f32)
+                }
+            }
+            Value::Str(s) => Statement::Str(s),
+            Value::Bool(b) => Statement::Integer(b as i32),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::Str(_) => "string",
+            Value::Bool(_) => "bool",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 16] = [
+    "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "^", "(", ")", "=", ";",
+];
+
+/// Executes the small subset of SQF used inside `__EVAL` and `__EXEC`
+///
+/// Supports numbers, strings, arithmetic, comparisons, a few math commands and variables assigned with `__EXEC`.
+#[derive(Debug, Clone, Default)]
+pub struct Executor {
+    variables: HashMap<String, Value>,
+}
+
+impl Executor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs the statements of an `__EXEC`, storing any assigned variables
+    pub fn exec(&mut self, code: &str) -> Result<(), String> {
+        for mut tokens in statements(tokenize(code)?) {
+            if let (Some(Token::Ident(ident)), Some(Token::Op("="))) = (tokens.first().cloned(), tokens.get(1)) {
+                tokens.drain(0..2);
+                let value = self.evaluate(tokens)?;
+                self.variables.insert(ident.to_lowercase(), value);
+            } else {
+                self.evaluate(tokens)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates the expression of an `__EVAL`
+    pub fn eval(&self, code: &str) -> Result<Value, String> {
+        let tokens = tokenize(code)?;
+        if tokens.is_empty() {
+            return Err("Empty expression".to_string());
+        }
+        self.evaluate(tokens)
+    }
+
+    fn evaluate(&self, tokens: Vec<Token>) -> Result<Value, String> {
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            variables: &self.variables,
+        };
+        let value = parser.binary(0)?;
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(format!("Unexpected {:?}", token));
+        }
+        Ok(value)
+    }
+}
+
+/// Splits tokens into statements at the `;` outside of parentheses, empty statements are skipped
+fn statements(tokens: Vec<Token>) -> Vec<Vec<Token>> {
+    let mut statements = vec![Vec::new()];
+    let mut depth = 0usize;
+    for token in tokens {
+        match token {
+            Token::Op(";") if depth == 0 => statements.push(Vec::new()),
+            _ => {
+                match token {
+                    Token::Op("(") => depth += 1,
+                    Token::Op(")") => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                statements.last_mut().unwrap().push(token);
+            }
+        }
+    }
+    statements.retain(|s| !s.is_empty());
+    statements
+}
+
+fn tokenize(code: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = code.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = if literal.starts_with("0x") || literal.starts_with("0X") {
+                i64::from_str_radix(&literal[2..], 16).map(|v| v as f64).ok()
+            } else {
+                literal.parse().ok()
+            };
+            tokens.push(Token::Number(value.ok_or_else(|| format!("Invalid number `{}`", literal))?));
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some(q) if *q == c => {
+                        if chars.get(i + 1) == Some(&c) {
+                            value.push(c);
+                            i += 2;
+                        } else {
+                            i += 1;
+                            break;
+                        }
+                    }
+                    Some(other) => {
+                        value.push(*other);
+                        i += 1;
+                    }
+                    None => return Err("Unterminated string".to_string()),
+                }
+            }
+            tokens.push(Token::Str(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect::<String>().to_lowercase()));
+        } else {
+            let rest: String = chars[i..].iter().take(2).collect();
+            match OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+                Some(op) => {
+                    tokens.push(Token::Op(op));
+                    i += op.len();
+                }
+                None => return Err(format!("Unexpected `{}`", c)),
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    variables: &'a HashMap<String, Value>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Result<Value, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op(op)) => *op,
+                Some(Token::Ident(ident)) if ident == "mod" || ident == "min" || ident == "max" => match ident.as_str() {
+                    "mod" => "%",
+                    "min" => "min",
+                    _ => "max",
+                },
+                _ => break,
+            };
+            let precedence = match precedence(op) {
+                Some(p) if p >= min_precedence => p,
+                _ => break,
+            };
+            self.position += 1;
+            let right = self.binary(precedence + 1)?;
+            left = apply(op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Value, String> {
+        match self.peek().cloned() {
+            Some(Token::Op("-")) => {
+                self.position += 1;
+                match self.unary()? {
+                    Value::Number(n) => Ok(Value::Number(-n)),
+                    v => Err(format!("Can not negate a {}", v.type_name())),
+                }
+            }
+            Some(Token::Op("+")) => {
+                self.position += 1;
+                self.unary()
+            }
+            Some(Token::Ident(ref ident)) if is_unary_command(ident) => {
+                self.position += 1;
+                let value = self.unary()?;
+                unary_command(ident, value)
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Value, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Value::Number(n)),
+            Some(Token::Str(s)) => Ok(Value::Str(s)),
+            Some(Token::Op("(")) => {
+                let value = self.binary(0)?;
+                match self.next() {
+                    Some(Token::Op(")")) => Ok(value),
+                    _ => Err("Expected `)`".to_string()),
+                }
+            }
+            Some(Token::Ident(ident)) => match ident.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                "pi" => Ok(Value::Number(std::f64::consts::PI)),
+                _ => self
+                    .variables
+                    .get(&ident)
+                    .cloned()
+                    .ok_or_else(|| format!("Undefined variable `{}`", ident)),
+            },
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn precedence(op: &str) -> Option<u8> {
+    Some(match op {
+        "==" | "!=" | "<" | ">" | "<=" | ">=" => 0,
+        "min" | "max" => 1,
+        "+" | "-" => 2,
+        "*" | "/" | "%" => 3,
+        "^" => 4,
+        _ => return None,
+    })
+}
+
+fn apply(op: &str, left: Value, right: Value) -> Result<Value, String> {
+    Ok(match (op, left, right) {
+        ("+", Value::Str(l), Value::Str(r)) => Value::Str(l + &r),
+        ("==", l, r) => Value::Bool(l == r),
+        ("!=", l, r) => Value::Bool(l != r),
+        (_, Value::Number(l), Value::Number(r)) => match op {
+            "+" => Value::Number(l + r),
+            "-" => Value::Number(l - r),
+            "*" => Value::Number(l * r),
+            "/" if r == 0.0 => return Err("Division by zero".to_string()),
+            "/" => Value::Number(l / r),
+            "%" => Value::Number(l % r),
+            "^" => Value::Number(l.powf(r)),
+            "min" => Value::Number(l.min(r)),
+            "max" => Value::Number(l.max(r)),
+            "<" => Value::Bool(l < r),
+            ">" => Value::Bool(l > r),
+            "<=" => Value::Bool(l <= r),
+            ">=" => Value::Bool(l >= r),
+            _ => unreachable!(),
+        },
+        (_, l, r) => return Err(format!("Can not use `{}` with {} and {}", op, l.type_name(), r.type_name())),
+    })
+}
+
+fn is_unary_command(ident: &str) -> bool {
+    ["floor", "ceil", "round", "abs", "sqrt", "str", "sin", "cos"].contains(&ident)
+}
+
+fn unary_command(command: &str, value: Value) -> Result<Value, String> {
+    Ok(match (command, value) {
+        ("str", Value::Number(n)) => Value::Str(n.to_string()),
+        ("str", Value::Str(s)) => Value::Str(format!("\"{}\"", s)),
+        ("str", Value::Bool(b)) => Value::Str(b.to_string()),
+        (_, Value::Number(n)) => Value::Number(match command {
+            "floor" => n.floor(),
+            "ceil" => n.ceil(),
+            "round" => n.round(),
+            "abs" => n.abs(),
+            "sqrt" => n.sqrt(),
+            "sin" => n.to_radians().sin(),
+            "cos" => n.to_radians().cos(),
+            _ => unreachable!(),
+        }),
+        (_, v) => return Err(format!("Can not use `{}` with {}", command, v.type_name())),
+    })
+}
+
+#[test]
+fn eval() {
+    let mut executor = Executor::new();
+    assert_eq!(executor.eval("1 + 2 * 3").unwrap(), Value::Number(7.0));
+    assert_eq!(executor.eval("(1 + 2) * 3 ^ 2").unwrap(), Value::Number(27.0));
+    assert_eq!(executor.eval("\"a\" + 'b'").unwrap(), Value::Str("ab".to_string()));
+    assert_eq!(executor.eval("floor 2.5 + 1").unwrap(), Value::Number(3.0));
+    assert!(executor.eval("_missing * 2").is_err());
+    executor.exec("_x = 2; _y = _x * 10").unwrap();
+    assert_eq!(executor.eval("_Y / _x").unwrap(), Value::Number(10.0));
+    executor.exec("_s = \"a;b\"; _t = 'c;' + _s;").unwrap();
+    assert_eq!(executor.eval("_t").unwrap(), Value::Str("c;a;b".to_string()));
+    assert_eq!(executor.eval("1/4").unwrap().into_statement(), Statement::Float(0.25));
+    assert_eq!(executor.eval("10 mod 4").unwrap().into_statement(), Statement::Integer(2));
+}
@@ -0,0 +1,274 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::pbo::Pbo;
+use crate::ArmaLintError;
+
+/// How the file of an `#include` was written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IncludeKind {
+    /// `#include "file.hpp"`, searched next to the including file first
+    Quoted,
+    /// `#include <file.hpp>`, only searched in the prefixes and search paths
+    System,
+}
+
+/// Finds the files of `#include` directives
+///
+/// Resolvers take `&self` so the parser can share them while it recurses, implementations that
+/// cache files or keep statistics use interior mutability.
+pub trait IncludeResolver {
+    /// Finds an included file, returning its content and the directory it is in
+    ///
+    /// `wd` is the directory of the including file as returned for it by the resolver. A file that
+    /// does not exist is `ArmaLintError::IncludeNotFound`, other errors mean it could not be read.
+    fn resolve(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Result<(String, PathBuf), ArmaLintError>;
+}
+
+impl<F> IncludeResolver for F
+where
+    F: Fn(&str, &Path, IncludeKind) -> Result<(String, PathBuf), ArmaLintError>,
+{
+    fn resolve(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Result<(String, PathBuf), ArmaLintError> {
+        self(filename, wd, kind)
+    }
+}
+
+fn not_found(filename: &str) -> ArmaLintError {
+    ArmaLintError::IncludeNotFound(filename.to_string())
+}
+
+/// Finds included files on disk
+///
+/// Virtual paths such as `\z\ace\addons\main\script_component.hpp` are mapped to directories with
+/// prefixes, either given directly or read from `$PBOPREFIX$` files. Names are matched case-insensitively
+/// when the exact path does not exist, like the engine's filesystem. Files are read once and cached.
+#[derive(Debug, Clone, Default)]
+pub struct FsResolver {
+    prefixes: Vec<(Vec<String>, PathBuf)>,
+    search_paths: Vec<PathBuf>,
+    cache: RefCell<HashMap<PathBuf, String>>,
+}
+
+impl FsResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps a virtual prefix such as `z\ace\addons\main` to a directory
+    pub fn prefix<P: Into<PathBuf>>(mut self, prefix: &str, dir: P) -> Self {
+        self.prefixes
+            .push((parts(prefix).iter().map(|p| p.to_lowercase()).collect(), dir.into()));
+        // The longest prefix is tried first
+        self.prefixes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        self
+    }
+
+    /// Adds a directory that is searched for every include
+    pub fn search_path<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.search_paths.push(dir.into());
+        self
+    }
+
+    /// Maps the prefix of every `$PBOPREFIX$` file in a directory and its subdirectories
+    ///
+    /// Hidden directories are skipped.
+    pub fn prefix_files(mut self, dir: &Path) -> Result<Self, ArmaLintError> {
+        let file = dir.join(Pbo::PREFIX_FILE);
+        if file.is_file() {
+            self = self.prefix_file(&file)?;
+        }
+        let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            if !entry.file_name().to_string_lossy().starts_with('.') && entry.path().is_dir() {
+                self = self.prefix_files(&entry.path())?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Maps the prefix of the addon a file is in, found in the closest `$PBOPREFIX$` above it
+    pub fn addon_of(self, file: &Path) -> Result<Self, ArmaLintError> {
+        for dir in file.ancestors().skip(1) {
+            let prefix_file = dir.join(Pbo::PREFIX_FILE);
+            if prefix_file.is_file() {
+                return self.prefix_file(&prefix_file);
+            }
+        }
+        Ok(self)
+    }
+
+    fn prefix_file(self, file: &Path) -> Result<Self, ArmaLintError> {
+        let mut content = String::new();
+        open_file!(file)?.read_to_string(&mut content)?;
+        let extensions = Pbo::parse_prefix_file(&content);
+        match extensions.iter().find(|(key, _)| key.eq_ignore_ascii_case("prefix")) {
+            Some((_, prefix)) => Ok(self.prefix(prefix, file.parent().unwrap())),
+            None => Ok(self),
+        }
+    }
+
+    /// Path on disk of an included file
+    pub fn find(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Option<PathBuf> {
+        let absolute = filename.starts_with('\\') || filename.starts_with('/');
+        let name = parts(filename);
+        if kind == IncludeKind::Quoted && !absolute {
+            if let Some(path) = find_insensitive(wd, &name) {
+                return Some(path);
+            }
+        }
+        if absolute {
+            let lower: Vec<String> = name.iter().map(|p| p.to_lowercase()).collect();
+            for (prefix, dir) in &self.prefixes {
+                if lower.starts_with(prefix) {
+                    if let Some(path) = find_insensitive(dir, &name[prefix.len()..]) {
+                        return Some(path);
+                    }
+                }
+            }
+        }
+        self.search_paths.iter().find_map(|dir| find_insensitive(dir, &name))
+    }
+}
+
+impl IncludeResolver for FsResolver {
+    /// Quoted names are searched next to the including file in `wd` first. Names starting with `\`
+    /// are looked up in the prefixes, then every name is searched in each search path.
+    fn resolve(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Result<(String, PathBuf), ArmaLintError> {
+        let path = self.find(filename, wd, kind).ok_or_else(|| not_found(filename))?;
+        let wd = path.parent().map_or_else(|| wd.to_path_buf(), Path::to_path_buf);
+        if let Some(content) = self.cache.borrow().get(&path) {
+            return Ok((content.clone(), wd));
+        }
+        let mut content = String::new();
+        open_file!(path)?.read_to_string(&mut content)?;
+        self.cache.borrow_mut().insert(path, content.clone());
+        Ok((content, wd))
+    }
+}
+
+/// Serves included files from memory, for tests and editors with unsaved files
+///
+/// Paths are virtual and case-insensitive, `\x\test\macros.hpp` and `x/test/macros.hpp` are the same file.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryResolver {
+    files: HashMap<String, String>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file, replacing any file with the same path
+    pub fn file(mut self, path: &str, content: &str) -> Self {
+        self.insert(path, content);
+        self
+    }
+
+    pub fn insert(&mut self, path: &str, content: &str) {
+        self.files.insert(key(&parts(path)), content.to_string());
+    }
+}
+
+impl IncludeResolver for MemoryResolver {
+    /// Quoted names are searched relative to `wd` first, every name is then searched from the root
+    fn resolve(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Result<(String, PathBuf), ArmaLintError> {
+        let absolute = filename.starts_with('\\') || filename.starts_with('/');
+        let mut candidates = Vec::new();
+        if kind == IncludeKind::Quoted && !absolute {
+            let wd = wd.to_string_lossy();
+            candidates.push(key(&parts(&format!("{}/{}", wd, filename))));
+        }
+        candidates.push(key(&parts(filename)));
+        for candidate in candidates {
+            if let Some(content) = self.files.get(&candidate) {
+                let wd = candidate.rfind('/').map_or("", |i| &candidate[..i]);
+                return Ok((content.clone(), PathBuf::from(wd)));
+            }
+        }
+        Err(not_found(filename))
+    }
+}
+
+/// Tries each resolver in turn, the first that finds the file is used
+///
+/// The next resolver is only tried when a file is not found, any other error is returned.
+#[derive(Default)]
+pub struct ChainResolver<'a> {
+    resolvers: Vec<&'a dyn IncludeResolver>,
+}
+
+impl<'a> ChainResolver<'a> {
+    pub fn new() -> Self {
+        Self { resolvers: Vec::new() }
+    }
+
+    pub fn with(mut self, resolver: &'a dyn IncludeResolver) -> Self {
+        self.resolvers.push(resolver);
+        self
+    }
+}
+
+impl IncludeResolver for ChainResolver<'_> {
+    fn resolve(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Result<(String, PathBuf), ArmaLintError> {
+        for resolver in &self.resolvers {
+            match resolver.resolve(filename, wd, kind) {
+                Err(ArmaLintError::IncludeNotFound(_)) => {}
+                result => return result,
+            }
+        }
+        Err(not_found(filename))
+    }
+}
+
+/// Parts of a path separated by `\` or `/`
+fn parts(path: &str) -> Vec<&str> {
+    path.split(['\\', '/']).filter(|p| !p.is_empty() && *p != ".").collect()
+}
+
+/// Case-insensitive key of a virtual path, with `..` applied
+fn key(parts: &[&str]) -> String {
+    let mut key: Vec<String> = Vec::new();
+    for part in parts {
+        if *part == ".." {
+            key.pop();
+        } else {
+            key.push(part.to_lowercase());
+        }
+    }
+    key.join("/")
+}
+
+/// Joins the parts to a directory, falling back to a case-insensitive match for each part
+fn find_insensitive(dir: &Path, parts: &[&str]) -> Option<PathBuf> {
+    let exact = parts.iter().fold(dir.to_path_buf(), |path, part| path.join(part));
+    if exact.is_file() {
+        return Some(exact);
+    }
+    let mut path = dir.to_path_buf();
+    for part in parts {
+        if *part == ".." {
+            path.push(part);
+            continue;
+        }
+        let candidate = path.join(part);
+        if candidate.exists() {
+            path = candidate;
+            continue;
+        }
+        path = std::fs::read_dir(&path)
+            .ok()?
+            .filter_map(Result::ok)
+            .find(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case(part))?
+            .path();
+    }
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::simplify::{Array, Class, Config, Entry};
+use super::{Code, Diagnostic, Span};
+
+/// A config with the inheritance of every class applied
+#[derive(Debug, Clone)]
+pub struct Resolved {
+    /// Every class with a body, parents before their children
+    pub classes: Vec<ResolvedClass>,
+    pub diagnostics: Vec<InheritanceError>,
+    index: HashMap<String, usize>,
+}
+
+/// The effective content of a class after inheritance
+#[derive(Debug, Clone)]
+pub struct ResolvedClass {
+    /// Path of the class from the root, `CfgVehicles/B_Car_F`
+    pub path: String,
+    /// Path of the parent class, external classes keep the path of their declaration
+    pub parent: Option<String>,
+    pub properties: Vec<(String, ResolvedProperty)>,
+    /// Name and path of every child class, inherited children point to the class of the parent
+    pub classes: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedProperty {
+    pub value: Entry,
+    /// Path of the class the value was set in
+    pub defined_in: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InheritanceError {
+    /// The classes that inherit from each other, the first class is repeated at the end
+    Circular(Vec<String>),
+    MissingParent {
+        class: String,
+        parent: String,
+    },
+}
+
+impl std::fmt::Display for InheritanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InheritanceError::Circular(chain) => write!(f, "Circular inheritance: {}", chain.join(" -> ")),
+            InheritanceError::MissingParent { class, parent } => {
+                write!(f, "Parent class `{}` of `{}` could not be found", parent, class)
+            }
+        }
+    }
+}
+
+impl InheritanceError {
+    /// Diagnostic for the rapified `file`, it points at the start of the file since it has no lines
+    pub fn diagnostic(&self, file: &str) -> Diagnostic {
+        let code = match self {
+            InheritanceError::Circular(_) => Code::CircularInheritance,
+            InheritanceError::MissingParent { .. } => Code::MissingParent,
+        };
+        let span = Span {
+            file: file.to_string(),
+            start: (1, 1),
+            end: (1, 1),
+        };
+        Diagnostic::new(code, self.to_string(), span)
+    }
+}
+
+impl Resolved {
+    /// Finds a class by its path, names are case-insensitive
+    ///
+    /// Inherited child classes can be found through the class that inherited them.
+    pub fn class(&self, path: &str) -> Option<&ResolvedClass> {
+        let mut current: Option<&ResolvedClass> = None;
+        for name in path.split('/').filter(|n| !n.is_empty()) {
+            let child = match current {
+                None => name.to_string(),
+                Some(class) => class
+                    .classes
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                    .map(|(_, p)| p.clone())?,
+            };
+            current = Some(&self.classes[*self.index.get(&child.to_lowercase())?]);
+        }
+        current
+    }
+}
+
+impl ResolvedClass {
+    /// Finds the effective value of a property, names are case-insensitive
+    pub fn property(&self, name: &str) -> Option<&ResolvedProperty> {
+        self.properties
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, p)| p)
+    }
+}
+
+impl Config {
+    /// Resolves the inheritance of every class
+    pub fn resolve(&self) -> Resolved {
+        Resolver::new(self).resolve()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Member {
+    Property(Entry, usize),
+    Class(Target),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Local(usize),
+    /// Path of a `class Name;` declaration of a class that is not defined in this config
+    External(String),
+}
+
+type Members = Rc<Vec<(String, Member)>>;
+
+struct Scope<'a> {
+    path: String,
+    class: &'a Class,
+    enclosing: Option<usize>,
+}
+
+struct Resolver<'a> {
+    scopes: Vec<Scope<'a>>,
+    members: Vec<Option<Members>>,
+    parents: Vec<Option<Option<Target>>>,
+    stack: Vec<usize>,
+    diagnostics: Vec<InheritanceError>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(config: &'a Config) -> Self {
+        let mut scopes = vec![Scope {
+            path: String::new(),
+            class: &config.root,
+            enclosing: None,
+        }];
+        let mut position = 0;
+        while position < scopes.len() {
+            for (name, entry) in &scopes[position].class.entries {
+                if let Entry::Class(class) = entry {
+                    if !class.external && !class.deletion {
+                        let path = join(&scopes[position].path, name);
+                        scopes.push(Scope {
+                            path,
+                            class,
+                            enclosing: Some(position),
+                        });
+                    }
+                }
+            }
+            position += 1;
+        }
+        let count = scopes.len();
+        Self {
+            scopes,
+            members: vec![None; count],
+            parents: vec![None; count],
+            stack: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn resolve(mut self) -> Resolved {
+        let mut classes = Vec::new();
+        let mut index = HashMap::new();
+        for i in 1..self.scopes.len() {
+            let members = self.members(i);
+            let parent = self.parent(i).map(|target| self.target_path(&target));
+            let mut class = ResolvedClass {
+                path: self.scopes[i].path.clone(),
+                parent,
+                properties: Vec::new(),
+                classes: Vec::new(),
+            };
+            for (name, member) in members.iter() {
+                match member {
+                    Member::Property(value, source) => class.properties.push((
+                        name.clone(),
+                        ResolvedProperty {
+                            value: value.clone(),
+                            defined_in: self.scopes[*source].path.clone(),
+                        },
+                    )),
+                    Member::Class(target) => {
+                        if let Target::Local(_) = target {
+                            class.classes.push((name.clone(), self.target_path(target)));
+                        }
+                    }
+                }
+            }
+            index.insert(class.path.to_lowercase(), classes.len());
+            classes.push(class);
+        }
+        Resolved {
+            classes,
+            diagnostics: self.diagnostics,
+            index,
+        }
+    }
+
+    fn target_path(&self, target: &Target) -> String {
+        match target {
+            Target::Local(i) => self.scopes[*i].path.clone(),
+            Target::External(path) => path.clone(),
+        }
+    }
+
+    /// The effective members of a class, inherited members first
+    fn members(&mut self, i: usize) -> Members {
+        if let Some(members) = &self.members[i] {
+            return members.clone();
+        }
+        if let Some(position) = self.stack.iter().position(|s| *s == i) {
+            let mut chain: Vec<String> = self.stack[position..].iter().map(|s| self.scopes[*s].path.clone()).collect();
+            chain.push(self.scopes[i].path.clone());
+            let error = InheritanceError::Circular(chain);
+            if !self.diagnostics.iter().any(|d| same_cycle(d, &error)) {
+                self.diagnostics.push(error);
+            }
+            return Rc::new(Vec::new());
+        }
+        self.stack.push(i);
+        let mut members = match self.parent(i) {
+            Some(Target::Local(parent)) => (*self.members(parent)).clone(),
+            _ => Vec::new(),
+        };
+        for (name, entry) in &self.scopes[i].class.entries {
+            let member = match entry {
+                Entry::Class(class) if class.deletion => {
+                    members.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+                    continue;
+                }
+                Entry::Class(class) if class.external => {
+                    if find(&members, name).is_some() {
+                        continue;
+                    }
+                    Member::Class(Target::External(join(&self.scopes[i].path, name)))
+                }
+                Entry::Class(_) => Member::Class(Target::Local(self.child(i, name).unwrap())),
+                Entry::Array(array) if array.expand => match find(&members, name) {
+                    Some(Member::Property(Entry::Array(inherited), _)) => {
+                        let mut elements = inherited.elements.clone();
+                        elements.extend(array.elements.iter().cloned());
+                        Member::Property(Entry::Array(Array { expand: false, elements }), i)
+                    }
+                    _ => Member::Property(
+                        Entry::Array(Array {
+                            expand: false,
+                            elements: array.elements.clone(),
+                        }),
+                        i,
+                    ),
+                },
+                Entry::Invisible(_) => continue,
+                value => Member::Property(value.clone(), i),
+            };
+            match members.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+                Some(existing) => *existing = (name.clone(), member),
+                None => members.push((name.clone(), member)),
+            }
+        }
+        self.stack.pop();
+        let members = Rc::new(members);
+        self.members[i] = Some(members.clone());
+        members
+    }
+
+    /// Finds the class `i` inherits from
+    ///
+    /// The parent is looked up in the enclosing scope first, then in what that scope inherited,
+    /// then in the scopes around it. `class Name;` declares a class that the scope inherited or
+    /// that is defined outside of this config.
+    fn parent(&mut self, i: usize) -> Option<Target> {
+        if let Some(parent) = &self.parents[i] {
+            return parent.clone();
+        }
+        // Guards against looking up the parent again while it is being resolved
+        self.parents[i] = Some(None);
+        let name = self.scopes[i].class.parent.clone();
+        let parent = if name.is_empty() {
+            None
+        } else {
+            let mut scope = self.scopes[i].enclosing;
+            let mut found = None;
+            while let Some(s) = scope {
+                let own = self.scopes[s]
+                    .class
+                    .entries
+                    .iter()
+                    .rev()
+                    .find(|(n, e)| n.eq_ignore_ascii_case(&name) && matches!(e, Entry::Class(_)))
+                    .map(|(_, e)| e);
+                let declared = match own {
+                    Some(Entry::Class(class)) if !class.external && !class.deletion => {
+                        let child = self.child(s, &name).unwrap();
+                        if child != i {
+                            found = Some(Target::Local(child));
+                            break;
+                        }
+                        false
+                    }
+                    Some(Entry::Class(class)) => class.external,
+                    _ => false,
+                };
+                if let Some(Target::Local(parent)) = self.parent(s) {
+                    if let Some(Member::Class(target)) = find(&self.members(parent), &name) {
+                        found = Some(target.clone());
+                        break;
+                    }
+                }
+                if declared {
+                    found = Some(Target::External(join(&self.scopes[s].path, &name)));
+                    break;
+                }
+                scope = self.scopes[s].enclosing;
+            }
+            if found.is_none() {
+                self.diagnostics.push(InheritanceError::MissingParent {
+                    class: self.scopes[i].path.clone(),
+                    parent: name,
+                });
+            }
+            found
+        };
+        self.parents[i] = Some(parent.clone());
+        parent
+    }
+
+    /// Index of the last class with a body named `name` defined directly in scope `i`
+    fn child(&self, i: usize, name: &str) -> Option<usize> {
+        let path = join(&self.scopes[i].path, name);
+        self.scopes
+            .iter()
+            .rposition(|s| s.enclosing == Some(i) && s.path.eq_ignore_ascii_case(&path))
+    }
+}
+
+fn find<'m>(members: &'m [(String, Member)], name: &str) -> Option<&'m Member> {
+    members.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, m)| m)
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", path, name)
+    }
+}
+
+/// Whether two errors report the same cycle, starting from a different class
+fn same_cycle(a: &InheritanceError, b: &InheritanceError) -> bool {
+    match (a, b) {
+        (InheritanceError::Circular(a), InheritanceError::Circular(b)) => {
+            a.len() == b.len() && b[..b.len() - 1].iter().all(|c| a.contains(c))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,291 @@
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashSet;
+use std::io::{BufReader, Read, Seek};
+use std::rc::Rc;
+
+use super::query::{self, Lookup, LookupEntry, Value};
+use super::rapify::{Reader, ROOT_OFFSET};
+use super::simplify::{Array, Class, Config, Entry};
+use crate::error::RapifiedError;
+use crate::ArmaLintError;
+
+/// A rapified config that reads class bodies when they are first used
+///
+/// Opening the config only reads the header and the enums. A lookup reads the bodies of the classes
+/// along its path, each body is read once and kept, so a single lookup in a large config stays fast.
+pub struct LazyConfig<I: Read + Seek> {
+    root: LazyClass<I>,
+    pub enums: Vec<(String, i32)>,
+}
+
+/// A class of a `LazyConfig`, the body is read when its parent or entries are first needed
+pub struct LazyClass<I: Read + Seek> {
+    external: bool,
+    deletion: bool,
+    source: Option<Source<I>>,
+    body: OnceCell<Body<I>>,
+}
+
+pub enum LazyEntry<I: Read + Seek> {
+    Str(String),
+    Float(f32),
+    Int(i32),
+    Array(Array),
+    Class(LazyClass<I>),
+}
+
+struct Input<I: Read + Seek> {
+    reader: RefCell<BufReader<I>>,
+    len: u64,
+}
+
+/// Where the body of a class is read from
+struct Source<I: Read + Seek> {
+    input: Rc<Input<I>>,
+    /// Where the offset of the body was read
+    read_at: u64,
+    offset: u32,
+    /// Body offsets of the classes around this one, used to find cycles
+    stack: Rc<Vec<u32>>,
+}
+
+struct Body<I: Read + Seek> {
+    parent: String,
+    entries: Vec<(String, LazyEntry<I>)>,
+}
+
+impl<I: Read + Seek> LazyConfig<I> {
+    /// Reads the header and the enums of a rapified config
+    pub fn open(input: I) -> Result<Self, ArmaLintError> {
+        let mut reader = BufReader::new(input);
+        let (len, enums) = {
+            let mut reader = Reader::new(&mut reader)?;
+            let enum_offset = reader.header()?;
+            (reader.len(), reader.enums(enum_offset)?)
+        };
+        let source = Source {
+            input: Rc::new(Input {
+                reader: RefCell::new(reader),
+                len,
+            }),
+            read_at: 12,
+            offset: ROOT_OFFSET,
+            stack: Rc::new(Vec::new()),
+        };
+        Ok(Self {
+            root: LazyClass {
+                external: false,
+                deletion: false,
+                source: Some(source),
+                body: OnceCell::new(),
+            },
+            enums,
+        })
+    }
+
+    pub fn root(&self) -> &LazyClass<I> {
+        &self.root
+    }
+
+    /// Finds an entry by its path, see `simplify::Config::get`
+    pub fn get(&self, path: &str) -> Result<Option<&LazyEntry<I>>, ArmaLintError> {
+        self.root.get(path)
+    }
+
+    /// Finds every entry matching a path, see `simplify::Config::get_all`
+    pub fn get_all(&self, path: &str) -> Result<Vec<(String, &LazyEntry<I>)>, ArmaLintError> {
+        self.root.get_all(path)
+    }
+
+    /// Reads every class body that has not been read yet
+    pub fn load(&self) -> Result<Config, ArmaLintError> {
+        Ok(Config {
+            root: self.root.load()?,
+            enums: self.enums.clone(),
+        })
+    }
+}
+
+impl<I: Read + Seek> LazyClass<I> {
+    pub fn external(&self) -> bool {
+        self.external
+    }
+
+    pub fn deletion(&self) -> bool {
+        self.deletion
+    }
+
+    /// Whether the body of the class has been read
+    pub fn is_loaded(&self) -> bool {
+        self.body.get().is_some()
+    }
+
+    pub fn parent(&self) -> Result<&str, ArmaLintError> {
+        Ok(&self.body()?.parent)
+    }
+
+    pub fn entries(&self) -> Result<&[(String, LazyEntry<I>)], ArmaLintError> {
+        Ok(&self.body()?.entries)
+    }
+
+    /// Finds an entry by its path relative to this class, see `simplify::Class::get`
+    pub fn get(&self, path: &str) -> Result<Option<&LazyEntry<I>>, ArmaLintError> {
+        query::get(self, path)
+    }
+
+    /// Finds every entry matching a path relative to this class, in the order they are first defined
+    pub fn get_all(&self, path: &str) -> Result<Vec<(String, &LazyEntry<I>)>, ArmaLintError> {
+        query::get_all(self, path)
+    }
+
+    /// Finds an entry directly in this class
+    pub fn entry(&self, name: &str) -> Result<Option<&LazyEntry<I>>, ArmaLintError> {
+        query::entry(self, name)
+    }
+
+    /// Reads the class and every class in it
+    pub fn load(&self) -> Result<Class, ArmaLintError> {
+        self.load_visited(&mut HashSet::new())
+    }
+
+    fn load_visited(&self, visited: &mut HashSet<u32>) -> Result<Class, ArmaLintError> {
+        if let Some(source) = &self.source {
+            if !visited.insert(source.offset) {
+                return Err(RapifiedError::SharedClassBody {
+                    offset: source.read_at,
+                    target: source.offset,
+                }
+                .into());
+            }
+        }
+        let body = self.body()?;
+        let mut entries = Vec::with_capacity(body.entries.len());
+        for (name, entry) in &body.entries {
+            let entry = match entry {
+                LazyEntry::Str(s) => Entry::Str(s.clone()),
+                LazyEntry::Float(f) => Entry::Float(*f),
+                LazyEntry::Int(i) => Entry::Int(*i),
+                LazyEntry::Array(a) => Entry::Array(a.clone()),
+                LazyEntry::Class(c) => Entry::Class(c.load_visited(visited)?),
+            };
+            entries.push((name.clone(), entry));
+        }
+        Ok(Class {
+            parent: body.parent.clone(),
+            external: self.external,
+            deletion: self.deletion,
+            entries,
+        })
+    }
+
+    fn body(&self) -> Result<&Body<I>, ArmaLintError> {
+        if let Some(body) = self.body.get() {
+            return Ok(body);
+        }
+        // Classes without a source are created with their body
+        let body = self.source.as_ref().unwrap().read()?;
+        Ok(self.body.get_or_init(|| body))
+    }
+}
+
+impl<I: Read + Seek> Source<I> {
+    /// Reads the body, class entries only get the offset of their own body
+    fn read(&self) -> Result<Body<I>, ArmaLintError> {
+        let mut input = self.input.reader.borrow_mut();
+        let mut reader = Reader::with_len(&mut *input, self.input.len);
+        reader.stack = (*self.stack).clone();
+        reader.stack.push(self.offset);
+        let stack = Rc::new(reader.stack.clone());
+        reader.seek(self.offset.into())?;
+        let (parent, entries) = reader.body(|reader| {
+            let (read_at, offset) = reader.class_offset()?;
+            Ok(LazyEntry::Class(LazyClass {
+                external: false,
+                deletion: false,
+                source: Some(Source {
+                    input: self.input.clone(),
+                    read_at,
+                    offset,
+                    stack: stack.clone(),
+                }),
+                body: OnceCell::new(),
+            }))
+        })?;
+        Ok(Body { parent, entries })
+    }
+}
+
+impl<I: Read + Seek> Lookup for LazyClass<I> {
+    type Entry = LazyEntry<I>;
+    type Error = ArmaLintError;
+
+    fn entries(&self) -> Result<&[(String, LazyEntry<I>)], ArmaLintError> {
+        LazyClass::entries(self)
+    }
+}
+
+impl<I: Read + Seek> LookupEntry for LazyEntry<I> {
+    type Class = LazyClass<I>;
+
+    fn value(&self) -> Option<Value<'_>> {
+        match self {
+            LazyEntry::Str(s) => Some(Value::Str(s)),
+            LazyEntry::Float(f) => Some(Value::Float(*f)),
+            LazyEntry::Int(i) => Some(Value::Int(*i)),
+            LazyEntry::Array(a) => Some(Value::Array(a)),
+            LazyEntry::Class(_) => None,
+        }
+    }
+
+    fn class(&self) -> Option<&LazyClass<I>> {
+        self.as_class()
+    }
+}
+
+impl<I: Read + Seek> LazyEntry<I> {
+    pub fn as_str(&self) -> Option<&str> {
+        self.value()?.as_str()
+    }
+
+    /// Numbers as `f32`, integers are converted
+    pub fn as_f32(&self) -> Option<f32> {
+        self.value()?.as_f32()
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        self.value()?.as_i32()
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        self.value()?.as_array()
+    }
+
+    pub fn as_class(&self) -> Option<&LazyClass<I>> {
+        match self {
+            LazyEntry::Class(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+/// Entries read from a class body, classes without a body are complete already
+impl<I: Read + Seek> From<Entry> for LazyEntry<I> {
+    fn from(entry: Entry) -> Self {
+        match entry {
+            Entry::Str(s) => LazyEntry::Str(s),
+            Entry::Float(f) => LazyEntry::Float(f),
+            Entry::Int(i) => LazyEntry::Int(i),
+            Entry::Array(a) => LazyEntry::Array(a),
+            Entry::Class(c) => LazyEntry::Class(LazyClass {
+                external: c.external,
+                deletion: c.deletion,
+                source: None,
+                body: OnceCell::from(Body {
+                    parent: c.parent,
+                    entries: Vec::new(),
+                }),
+            }),
+            Entry::Invisible(_) => unreachable!("rapified configs have no invisible entries"),
+        }
+    }
+}
@@ -1,7 +1,16 @@
 mod parser;
-pub use parser::{parse, parse_with_resolver, Node, Statement, AST};
+pub use parser::{parse, parse_cst, parse_cst_with_resolver, parse_with_resolver, Files, Node, Statement, Trivia, AST, CST};
 
+mod include;
+pub use include::{ChainResolver, FsResolver, IncludeKind, IncludeResolver, MemoryResolver};
+
+mod diagnostic;
+pub use diagnostic::{Code, Diagnostic, Fix, Label, Severity, Span};
+
+mod condition;
+mod exec;
 mod preprocess;
+mod query;
 pub use preprocess::PreProcessor;
 
 mod report;
@@ -10,6 +19,9 @@ pub use report::Report;
 pub mod render;
 pub use render::{RenderOptions, Renderer};
 
+pub mod inheritance;
+pub mod lazy;
+pub mod output;
 pub mod rapify;
 pub mod simplify;
 
@@ -18,12 +30,28 @@ fn get_ident(stmt: Statement) -> Result<String, crate::ArmaLintError> {
         Statement::Ident(val) => val,
         Statement::IdentArray(val) => val,
         Statement::InternalStr(val) => val,
+        Statement::Integer(val) => val.to_string(),
+        Statement::Float(val) => val.to_string(),
+        Statement::Bool(val) => val.to_string(),
         Statement::Processed(val, _) => get_ident(*val)?,
         Statement::Defined(val, _) => get_ident(val.statement)?,
         _ => panic!("get ident wasn't given ident: {:#?}", stmt),
     })
 }
 
+/// Resolves the value of each enum member, members without a value follow the previous member
+pub fn enum_values(members: &[(String, Option<i32>)]) -> Vec<(String, i32)> {
+    let mut next = 0;
+    members
+        .iter()
+        .map(|(name, value)| {
+            let value = value.unwrap_or(next);
+            next = value.wrapping_add(1);
+            (name.clone(), value)
+        })
+        .collect()
+}
+
 // Tests
 
 #[test]
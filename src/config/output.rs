@@ -0,0 +1,199 @@
+//! Machine-readable output of diagnostics for CI and code scanning
+
+use serde_json::{json, Value};
+
+use super::{Code, Diagnostic, Span};
+
+/// Message with the help and note appended, for formats without fields for them
+fn text(diagnostic: &Diagnostic) -> String {
+    let mut text = diagnostic.message.clone();
+    if let Some(help) = &diagnostic.help {
+        text.push_str(&format!("\nhelp: {}", help));
+    }
+    if let Some(note) = &diagnostic.note {
+        text.push_str(&format!("\nnote: {}", note));
+    }
+    text
+}
+
+fn span(span: &Span) -> Value {
+    json!({
+        "file": span.file,
+        "line": span.start.0,
+        "column": span.start.1,
+        "end_line": span.end.0,
+        "end_column": span.end.1,
+    })
+}
+
+/// A JSON array with an object for each diagnostic
+///
+/// ```json
+/// [{"code": "CFG006", "severity": "warning", "message": "...", "help": "use `SPEED`", "note": null,
+///   "file": "config.cpp", "line": 1, "column": 9, "end_line": 1, "end_column": 14, "labels": [], "fix": {...}}]
+/// ```
+pub fn json(diagnostics: &[Diagnostic]) -> String {
+    let values: Vec<Value> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut value = span(&d.span);
+            value["code"] = json!(d.code.as_str());
+            value["severity"] = json!(d.severity.to_string());
+            value["message"] = json!(d.message);
+            value["help"] = json!(d.help);
+            value["note"] = json!(d.note);
+            value["labels"] = d
+                .labels
+                .iter()
+                .map(|l| {
+                    let mut label = span(&l.span);
+                    label["message"] = json!(l.message);
+                    label
+                })
+                .collect();
+            value["fix"] = match &d.fix {
+                Some(fix) => {
+                    let mut value = span(&fix.span);
+                    value["replacement"] = json!(fix.replacement);
+                    value
+                }
+                None => Value::Null,
+            };
+            value
+        })
+        .collect();
+    serde_json::to_string_pretty(&values).unwrap()
+}
+
+fn region(span: &Span) -> Value {
+    json!({
+        "startLine": span.start.0,
+        "startColumn": span.start.1,
+        "endLine": span.end.0,
+        "endColumn": span.end.1,
+    })
+}
+
+fn location(span: &Span) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": span.file.replace('\\', "/") },
+            "region": region(span),
+        }
+    })
+}
+
+/// A SARIF 2.1.0 log with a single run, as read by GitHub code scanning
+pub fn sarif(diagnostics: &[Diagnostic]) -> String {
+    let rules: Vec<Value> = Code::ALL
+        .iter()
+        .map(|code| {
+            json!({
+                "id": code.as_str(),
+                "shortDescription": { "text": code.description() },
+                "defaultConfiguration": { "level": code.severity().to_string() },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut result = json!({
+                "ruleId": d.code.as_str(),
+                "ruleIndex": Code::ALL.iter().position(|c| *c == d.code).unwrap(),
+                "level": d.severity.to_string(),
+                "message": { "text": text(d) },
+                "locations": [location(&d.span)],
+            });
+            if !d.labels.is_empty() {
+                result["relatedLocations"] = d
+                    .labels
+                    .iter()
+                    .enumerate()
+                    .map(|(id, l)| {
+                        let mut location = location(&l.span);
+                        location["id"] = json!(id);
+                        location["message"] = json!({ "text": l.message });
+                        location
+                    })
+                    .collect();
+            }
+            if let Some(fix) = &d.fix {
+                result["fixes"] = json!([{
+                    "description": { "text": d.help.clone().unwrap_or_else(|| d.message.clone()) },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": fix.span.file.replace('\\', "/") },
+                        "replacements": [{
+                            "deletedRegion": region(&fix.span),
+                            "insertedContent": { "text": fix.replacement },
+                        }],
+                    }],
+                }]);
+            }
+            result
+        })
+        .collect();
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "ArmaLint",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&log).unwrap()
+}
+
+/// Escapes text for an XML attribute
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Checkstyle XML, diagnostics are grouped by file in the order the files first appear
+///
+/// Checkstyle has no end of a range, only the line and column the diagnostic starts at are written.
+pub fn checkstyle(diagnostics: &[Diagnostic]) -> String {
+    let mut files: Vec<(&str, Vec<&Diagnostic>)> = Vec::new();
+    for diagnostic in diagnostics {
+        match files.iter_mut().find(|(file, _)| *file == diagnostic.span.file) {
+            Some((_, list)) => list.push(diagnostic),
+            None => files.push((&diagnostic.span.file, vec![diagnostic])),
+        }
+    }
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n");
+    for (file, list) in files {
+        output.push_str(&format!("  <file name=\"{}\">\n", escape(file)));
+        for d in list {
+            output.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"armalint.{}\"/>\n",
+                d.span.start.0,
+                d.span.start.1,
+                d.severity,
+                escape(&text(d)),
+                d.code
+            ));
+        }
+        output.push_str("  </file>\n");
+    }
+    output.push_str("</checkstyle>");
+    output
+}
@@ -1,7 +1,8 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use super::Report;
+use super::{Code, Diagnostic, FsResolver, IncludeResolver, Report, Span};
 use crate::ArmaLintError;
 
 mod node;
@@ -10,19 +11,32 @@ pub use node::Node;
 mod statement;
 pub use statement::Statement;
 
+mod trivia;
+pub use trivia::Trivia;
+
+mod recovery;
+use recovery::SyntaxError;
+
 use pest::Parser;
 
 #[derive(Parser)]
 #[grammar = "config/config.pest"]
 pub struct ConfigParser;
 
+/// Content of every file of an AST by name, macro bodies have the file and line they were defined at
+pub type Files = HashMap<String, (Option<(String, usize)>, String)>;
+
 #[derive(Debug, Clone)]
 /// Abstract Syntax Tree
 pub struct AST {
     pub config: Node,
-    pub files: HashMap<String, (Option<(String, usize)>, String)>,
+    pub files: Files,
     pub processed: bool,
     pub report: Option<Report>,
+    /// Every `#include` as the including and the included file, in the order they appear
+    ///
+    /// The names are the keys of `files`.
+    pub includes: Vec<(String, String)>,
 }
 
 impl AST {
@@ -35,6 +49,44 @@ impl AST {
     }
 }
 
+/// Lossless Concrete Syntax Tree
+///
+/// An AST where comments and blank lines are attached to the nodes with `Statement::Annotated`,
+/// trivia at the end of a class body or file is kept in a `Statement::Trivia` node.
+pub type CST = AST;
+
+/// Converts a raw string into a lossless CST
+///
+/// ```
+/// let content = "// The value\nvalue = 123; // Trailing\n";
+/// armalint::config::parse_cst("config.cpp", content);
+/// ```
+pub fn parse_cst(file: &str, source: &str) -> Result<CST, ArmaLintError> {
+    let mut ast = parse(file, source)?;
+    let clean = ast.files.get(file).unwrap().1.clone();
+    if let Statement::Config(nodes) = ast.config.statement {
+        ast.config.statement = Statement::Config(trivia::attach_config(nodes, &clean));
+    }
+    Ok(ast)
+}
+
+/// Converts a raw string into a lossless CST using a custom resolver
+///
+/// The resolver is used to find files for #include, see `parse_with_resolver`
+pub fn parse_cst_with_resolver(
+    file: &str,
+    wd: PathBuf,
+    source: &str,
+    resolver: &dyn IncludeResolver,
+) -> Result<CST, ArmaLintError> {
+    let mut ast = parse_with_resolver(file, wd, source, resolver)?;
+    let clean = ast.files.get(file).unwrap().1.clone();
+    if let Statement::Config(nodes) = ast.config.statement {
+        ast.config.statement = Statement::Config(trivia::attach_config(nodes, &clean));
+    }
+    Ok(ast)
+}
+
 /// Converts a raw string into an AST
 ///
 /// ```
@@ -42,65 +94,72 @@ impl AST {
 /// armalint::config::parse("config.cpp", content);
 /// ```
 pub fn parse(file: &str, source: &str) -> Result<AST, ArmaLintError> {
-    if source.starts_with("#s") {
-        return Err(ArmaLintError::NotRoot);
-    }
-    let clean = source.replace("\r", "");
-    let mut files = HashMap::new();
-    files.insert(file.to_string(), (None, clean.to_string()));
-    let pair = ConfigParser::parse(Rule::file, &clean)?
-        .next()
-        .ok_or_else(|| ArmaLintError::InvalidInput(clean.clone()))?;
-    let pair = pair.into_inner().next().unwrap();
-    let (config, included) = Node::from_expr(file, std::env::current_dir().unwrap(), source, pair, |filename, wd| {
-        match std::fs::read_to_string(filename) {
-            Ok(content) =>  {
-                Ok((content, wd.clone()))
-            }
-            Err(e) => Err(e.into())
-        }
-    })?;
-    included.into_iter().for_each(|x| {
-        files.insert(x.0, (x.1, x.2));
-    });
-    Ok(AST {
-        config,
-        files,
-        processed: false,
-        report: None,
-    })
+    let wd = std::env::current_dir()?;
+    let resolver = FsResolver::new().search_path(&wd);
+    parse_with_resolver(file, wd, source, &resolver)
 }
 
 /// Use a custom resolver
 ///
 /// The resolver is used to find files for #include
 /// ```
-/// let content = "#include <myfile.hpp>";
+/// use armalint::config::MemoryResolver;
 ///
-/// armalint::config::parse_with_resolver("config.cpp", std::env::current_dir().unwrap(), content, |filename, wd| {
-///     match std::fs::read_to_string(filename) {
-///         Ok(content) =>  {
-///             Ok((content, wd.clone()))
-///         }
-///         Err(e) => Err(e.into())
-///     }
-/// });
+/// let content = "#include <x\\test\\macros.hpp>";
+/// let resolver = MemoryResolver::new().file("x/test/macros.hpp", "#define SPEED 120");
+///
+/// armalint::config::parse_with_resolver("config.cpp", std::env::current_dir().unwrap(), content, &resolver).unwrap();
 /// ```
-pub fn parse_with_resolver<F>(file: &str, wd: PathBuf, source: &str, resolver: F) -> Result<AST, ArmaLintError>
-where
-    F: Fn(&str, &PathBuf) -> Result<(String, PathBuf), ArmaLintError> + Copy,
-{
+///
+/// Every syntax error is collected, the parser skips to the end of the statement with the error
+/// and continues. They are returned as `ArmaLintError::SyntaxErrors` with the errors in the report.
+pub fn parse_with_resolver(
+    file: &str,
+    wd: PathBuf,
+    source: &str,
+    resolver: &dyn IncludeResolver,
+) -> Result<AST, ArmaLintError> {
+    parse_source(file, wd, source, resolver, true)
+}
+
+/// Parses a root file, recovering from syntax errors when `recover` is set
+fn parse_source(
+    file: &str,
+    wd: PathBuf,
+    source: &str,
+    resolver: &dyn IncludeResolver,
+    recover: bool,
+) -> Result<AST, ArmaLintError> {
+    let includes = Includes::default();
+    let root = includes.enter(&wd, file)?;
+    let mut ast = parse_file(file, wd, source, resolver, recover, &includes)?;
+    drop(root);
+    ast.includes = includes.edges.into_inner();
+    Ok(ast)
+}
+
+/// Parses a file, the root or an included file, with the includes of the parse it is part of
+fn parse_file(
+    file: &str,
+    wd: PathBuf,
+    source: &str,
+    resolver: &dyn IncludeResolver,
+    recover: bool,
+    includes: &Includes,
+) -> Result<AST, ArmaLintError> {
     if source.starts_with("#s") {
         return Err(ArmaLintError::NotRoot);
     }
     let clean = source.replace("\r", "");
     let mut files = HashMap::new();
     files.insert(file.to_string(), (None, clean.to_string()));
-    let pair = ConfigParser::parse(Rule::file, &clean)?
-        .next()
-        .ok_or_else(|| ArmaLintError::InvalidInput(clean.clone()))?;
+    let pair = match ConfigParser::parse(Rule::file, &clean) {
+        Ok(mut pairs) => pairs.next().ok_or_else(|| ArmaLintError::InvalidInput(clean.clone()))?,
+        Err(err) if recover => return Err(syntax_errors(file, &clean, err, files)),
+        Err(err) => return Err(err.into()),
+    };
     let pair = pair.into_inner().next().unwrap();
-    let (config, included) = Node::from_expr(file, wd, source, pair, resolver)?;
+    let (config, included) = Node::from_expr(file, wd, source, pair, resolver, includes)?;
     included.into_iter().for_each(|x| {
         files.insert(x.0, (x.1, x.2));
     });
@@ -109,9 +168,125 @@ where
         files,
         processed: false,
         report: None,
+        includes: Vec::new(),
     })
 }
 
+/// Collects the syntax errors of a file, skipping past each one until the rest of the file parses
+fn syntax_errors(file: &str, source: &str, err: pest::error::Error<Rule>, files: Files) -> ArmaLintError {
+    let mut errors: Vec<Diagnostic> = Vec::new();
+    let mut repaired = source.to_string();
+    let mut err = Some(err);
+    while let Some(current) = err.take() {
+        let error = SyntaxError::new(&repaired, &current);
+        let diagnostic = error.diagnostic(file, source);
+        if errors.last() == Some(&diagnostic) {
+            break;
+        }
+        errors.push(diagnostic);
+        if errors.len() < recovery::MAX_ERRORS && error.repair(&mut repaired) {
+            err = ConfigParser::parse(Rule::file, &repaired).err();
+        }
+    }
+    syntax_error_ast(file, files, errors)
+}
+
+/// A syntax error in a file that parsed, such as a number that is too large
+pub(super) fn invalid(
+    file: &str,
+    source: &str,
+    pair: &pest::iterators::Pair<Rule>,
+    message: String,
+    help: &str,
+) -> ArmaLintError {
+    let span = Span {
+        file: file.to_string(),
+        start: pair.as_span().start_pos().line_col(),
+        end: pair.as_span().end_pos().line_col(),
+    };
+    let mut files = HashMap::new();
+    files.insert(file.to_string(), (None, source.replace("\r", "")));
+    syntax_error_ast(
+        file,
+        files,
+        vec![Diagnostic::new(Code::SyntaxError, message, span).help(help)],
+    )
+}
+
+fn syntax_error_ast(file: &str, files: Files, errors: Vec<Diagnostic>) -> ArmaLintError {
+    let mut report = Report::new();
+    report.errors = errors;
+    ArmaLintError::SyntaxErrors(Box::new(AST {
+        config: Node {
+            file: file.to_string(),
+            start: (0, (1, 1)),
+            end: (0, (1, 1)),
+            line: String::new(),
+            statement: Statement::Config(Vec::new()),
+        },
+        files,
+        processed: false,
+        report: Some(report),
+        includes: Vec::new(),
+    }))
+}
+
+/// The files of a parse that are being read, used to find include cycles
+#[derive(Default)]
+pub(super) struct Includes {
+    /// Files being parsed from the root to the innermost include, by key and name
+    stack: RefCell<Vec<(String, String)>>,
+    /// Every include as the including and the included file
+    edges: RefCell<Vec<(String, String)>>,
+}
+
+/// Keeps a file on the include stack while it is parsed
+pub(super) struct Including<'a> {
+    includes: &'a Includes,
+}
+
+impl Includes {
+    /// Pushes a file found in `wd`, failing with the chain of includes when it is already being parsed
+    pub(super) fn enter(&self, wd: &Path, name: &str) -> Result<Including<'_>, ArmaLintError> {
+        let key = include_key(wd, name);
+        let mut stack = self.stack.borrow_mut();
+        if let Some(start) = stack.iter().position(|(k, _)| *k == key) {
+            let mut chain: Vec<String> = stack[start..].iter().map(|(_, n)| n.clone()).collect();
+            chain.push(name.to_string());
+            return Err(ArmaLintError::IncludeCycle(chain));
+        }
+        if let Some((_, parent)) = stack.last() {
+            self.edges.borrow_mut().push((parent.clone(), name.to_string()));
+        }
+        stack.push((key, name.to_string()));
+        Ok(Including { includes: self })
+    }
+}
+
+impl Drop for Including<'_> {
+    fn drop(&mut self) {
+        self.includes.stack.borrow_mut().pop();
+    }
+}
+
+/// Identifies a file found in `wd`, the same for every way the include is written
+fn include_key(wd: &Path, name: &str) -> String {
+    let file = name.rsplit(['\\', '/']).next().unwrap_or(name);
+    let path = wd.join(file).to_string_lossy().replace('\\', "/").to_lowercase();
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "." => {}
+            "" if !parts.is_empty() => {}
+            ".." if parts.last().is_some_and(|p| !p.is_empty() && *p != "..") => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
 // Error handling
 
 impl From<pest::error::Error<Rule>> for ArmaLintError {
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
-use super::{Rule, Statement};
+use super::{Includes, Rule, Statement};
+use crate::config::{IncludeKind, IncludeResolver};
 use crate::ArmaLintError;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -15,16 +16,15 @@ pub struct Node {
 type ResultNodeVec = Result<Vec<Node>, ArmaLintError>;
 
 impl Node {
-    pub fn from_expr<F>(
+    /// Builds the node of a pair, files are included with `resolver` and tracked in `includes`
+    pub(super) fn from_expr(
         file: &str,
         wd: PathBuf,
         source: &str,
         pair: pest::iterators::Pair<Rule>,
-        resolver: F,
-    ) -> Result<(Node, Vec<(String, Option<(String, usize)>, String)>), ArmaLintError>
-    where
-        F: Fn(&str, &PathBuf) -> Result<(String, PathBuf), ArmaLintError> + Copy,
-    {
+        resolver: &dyn IncludeResolver,
+        includes: &Includes,
+    ) -> Result<(Node, Vec<(String, Option<(String, usize)>, String)>), ArmaLintError> {
         let mut included: Vec<(String, Option<(String, usize)>, String)> = Vec::new();
         let node = Node {
             file: file.to_string(),
@@ -35,7 +35,7 @@ impl Node {
                 Rule::config => Statement::Config(
                     pair.into_inner()
                         .map(|x| {
-                            let r = Node::from_expr(file, wd.clone(), source, x, resolver);
+                            let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
                             if let Ok((n, i)) = r {
                                 i.iter().for_each(|x| included.push(x.clone()));
                                 Ok(n)
@@ -49,14 +49,15 @@ impl Node {
                     let mut parts = pair.into_inner();
                     Statement::Class {
                         ident: Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         }),
                         extends: None,
                         props: parts
                             .map(|x| {
-                                let r = Node::from_expr(file, wd.clone(), source, x, resolver);
+                                let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
                                 if let Ok((n, i)) = r {
                                     i.iter().for_each(|x| included.push(x.clone()));
                                     Ok(n)
@@ -71,18 +72,20 @@ impl Node {
                     let mut parts = pair.into_inner();
                     Statement::Class {
                         ident: Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         }),
                         extends: Some(Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         })),
                         props: parts
                             .map(|x| {
-                                let r = Node::from_expr(file, wd.clone(), source, x, resolver);
+                                let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
                                 if let Ok((n, i)) = r {
                                     i.iter().for_each(|x| included.push(x.clone()));
                                     Ok(n)
@@ -94,25 +97,50 @@ impl Node {
                     }
                 }
                 Rule::classdef => Statement::ClassDef(Box::new({
-                    let (n, i) = Node::from_expr(file, wd.clone(), source, pair.into_inner().next().unwrap(), resolver)?;
+                    let (n, i) = Node::from_expr(
+                        file,
+                        wd.clone(),
+                        source,
+                        pair.into_inner().next().unwrap(),
+                        resolver,
+                        includes,
+                    )?;
                     i.iter().for_each(|x| included.push(x.clone()));
                     n
                 })),
                 Rule::classdelete => Statement::ClassDelete(Box::new({
-                    let (n, i) = Node::from_expr(file, wd.clone(), source, pair.into_inner().next().unwrap(), resolver)?;
+                    let (n, i) = Node::from_expr(
+                        file,
+                        wd.clone(),
+                        source,
+                        pair.into_inner().next().unwrap(),
+                        resolver,
+                        includes,
+                    )?;
                     i.iter().for_each(|x| included.push(x.clone()));
                     n
                 })),
+                Rule::enumeration => Statement::Enum(
+                    pair.into_inner()
+                        .map(|x| {
+                            let mut parts = x.into_inner();
+                            let ident = String::from(parts.next().unwrap().as_str());
+                            Ok((ident, parts.next().map(|v| integer(file, source, &v)).transpose()?))
+                        })
+                        .collect::<Result<_, ArmaLintError>>()?,
+                ),
                 Rule::prop => {
                     let mut parts = pair.into_inner();
                     Statement::Property {
                         ident: Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         }),
                         value: Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         }),
@@ -123,12 +151,14 @@ impl Node {
                     let mut parts = pair.into_inner();
                     Statement::Property {
                         ident: Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         }),
                         value: Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         }),
@@ -139,7 +169,7 @@ impl Node {
                 Rule::array => Statement::Array(
                     pair.into_inner()
                         .map(|x| {
-                            let r = Node::from_expr(file, wd.clone(), source, x, resolver);
+                            let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
                             if let Ok((n, i)) = r {
                                 i.iter().for_each(|x| included.push(x.clone()));
                                 Ok(n)
@@ -150,7 +180,7 @@ impl Node {
                         .collect::<ResultNodeVec>()?,
                 ),
                 Rule::float => Statement::Float(pair.as_str().parse().unwrap()),
-                Rule::integer => Statement::Integer(pair.as_str().parse().unwrap()),
+                Rule::integer => Statement::Integer(integer(file, source, &pair)?),
                 Rule::string => Statement::Str(String::from(pair.as_str())),
                 Rule::ident => Statement::Ident(String::from(pair.as_str())),
                 Rule::identarray => Statement::IdentArray(String::from(pair.into_inner().next().unwrap().as_str())),
@@ -158,7 +188,7 @@ impl Node {
                 Rule::unquoted => Statement::Unquoted(
                     pair.into_inner()
                         .map(|x| {
-                            let r = Node::from_expr(file, wd.clone(), source, x, resolver);
+                            let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
                             if let Ok((n, i)) = r {
                                 i.iter().for_each(|x| included.push(x.clone()));
                                 Ok(n)
@@ -174,19 +204,34 @@ impl Node {
                     "__LINE__" => Statement::LINE,
                     _ => panic!("Special was not handled. Please report this to ArmaLint"),
                 },
+                Rule::eval => Statement::Eval(String::from(pair.into_inner().next().unwrap().as_str())),
+                Rule::exec => Statement::Exec(String::from(pair.into_inner().next().unwrap().as_str())),
                 // Directives
                 Rule::include => {
-                    let filename = pair.into_inner().next().unwrap().as_str();
-                    let content = &resolver(filename, &wd)?;
+                    let inner = pair.into_inner().next().unwrap();
+                    let kind = match inner.as_rule() {
+                        Rule::include_file => IncludeKind::System,
+                        _ => IncludeKind::Quoted,
+                    };
+                    let filename = inner.as_str();
+                    let content = &resolver.resolve(filename, &wd, kind)?;
+                    let _including = includes.enter(&content.1, filename)?;
                     included.push((filename.to_string(), None, content.0.to_string()));
-                    super::parse_with_resolver(filename, content.1.clone(), &content.0, resolver)?.config.statement
+                    Statement::Include {
+                        path: filename.to_string(),
+                        file: super::include_key(&content.1, filename),
+                        content: Box::new(
+                            super::parse_file(filename, content.1.clone(), &content.0, resolver, true, includes)?.config,
+                        ),
+                    }
                 }
                 Rule::define => {
                     let mut parts = pair.into_inner();
                     Statement::Define {
                         ident: String::from(parts.next().unwrap().as_str()),
                         value: Some(Box::new({
-                            let (n, i) = Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver)?;
+                            let (n, i) =
+                                Node::from_expr(file, wd.clone(), source, parts.next().unwrap(), resolver, includes)?;
                             i.iter().for_each(|x| included.push(x.clone()));
                             n
                         })),
@@ -205,11 +250,13 @@ impl Node {
                         ident: ident.to_string(),
                         args: args.into_inner().map(|x| String::from(x.as_str())).collect::<Vec<String>>(),
                         value: {
-                            if let Ok(stmt) = super::parse_with_resolver(
+                            if let Ok(stmt) = super::parse_file(
                                 &format!("MACRO:{}", ident),
                                 wd.clone(),
                                 &format!("{};", body.as_str().trim_end_matches('\n').replace("\\\n", "\n")),
                                 resolver,
+                                false,
+                                includes,
                             ) {
                                 included.push((
                                     format!("MACRO:{}", ident),
@@ -219,7 +266,8 @@ impl Node {
                                 Box::new(stmt.config)
                             } else {
                                 Box::new({
-                                    let (n, i) = Node::from_expr(file, wd.clone(), source, body.clone(), resolver)?;
+                                    let (n, i) =
+                                        Node::from_expr(file, wd.clone(), source, body.clone(), resolver, includes)?;
                                     i.iter().for_each(|x| included.push(x.clone()));
                                     n
                                 })
@@ -236,7 +284,7 @@ impl Node {
                             .unwrap()
                             .into_inner()
                             .map(|x| {
-                                let r = Node::from_expr(file, wd.clone(), source, x, resolver);
+                                let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
                                 if let Ok((n, i)) = r {
                                     i.iter().for_each(|x| included.push(x.clone()));
                                     Ok(n)
@@ -250,7 +298,7 @@ impl Node {
                 Rule::macro_call_arg => Statement::MacroCallArg(
                     pair.into_inner()
                         .map(|x| {
-                            let r = Node::from_expr(file, wd.clone(), source, x, resolver);
+                            let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
                             if let Ok((n, i)) = r {
                                 i.iter().for_each(|x| included.push(x.clone()));
                                 Ok(n)
@@ -263,42 +311,60 @@ impl Node {
                 Rule::macro_arg_char => Statement::Char(pair.as_str().chars().nth(0).unwrap()),
                 Rule::define_macro_body => Statement::MacroBody(pair.as_str().trim_end_matches('\n').to_owned()),
                 Rule::undef => Statement::Undefine(pair.into_inner().next().unwrap().as_str().to_string()),
-                Rule::ifdef => {
+                Rule::ifdef | Rule::ifndef | Rule::ifcond | Rule::elif => {
+                    let rule = pair.as_rule();
                     let mut parts = pair.into_inner();
-                    Statement::IfDef {
-                        ident: String::from(parts.next().unwrap().as_str()),
-                        positive: parts
-                            .next()
-                            .unwrap()
-                            .into_inner()
-                            .map(|x| {
-                                let r = Node::from_expr(file, wd.clone(), source, x, resolver);
-                                if let Ok((n, i)) = r {
-                                    i.iter().for_each(|x| included.push(x.clone()));
-                                    Ok(n)
-                                } else {
-                                    Err(r.err().unwrap())
-                                }
-                            })
-                            .collect::<ResultNodeVec>()?,
-                        negative: {
-                            if let Some(part) = parts.next() {
-                                Some(
-                                    part.into_inner()
-                                        .map(|x| {
-                                            let r = Node::from_expr(file, wd.clone(), source, x, resolver);
-                                            if let Ok((n, i)) = r {
-                                                i.iter().for_each(|x| included.push(x.clone()));
-                                                Ok(n)
-                                            } else {
-                                                Err(r.err().unwrap())
-                                            }
-                                        })
-                                        .collect::<ResultNodeVec>()?,
-                                )
+                    let ident = String::from(parts.next().unwrap().as_str().trim());
+                    let positive = parts
+                        .next()
+                        .unwrap()
+                        .into_inner()
+                        .map(|x| {
+                            let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
+                            if let Ok((n, i)) = r {
+                                i.iter().for_each(|x| included.push(x.clone()));
+                                Ok(n)
                             } else {
-                                None
+                                Err(r.err().unwrap())
                             }
+                        })
+                        .collect::<ResultNodeVec>()?;
+                    let negative = if let Some(part) = parts.next() {
+                        Some(if part.as_rule() == Rule::elif {
+                            let (n, i) = Node::from_expr(file, wd.clone(), source, part, resolver, includes)?;
+                            i.iter().for_each(|x| included.push(x.clone()));
+                            vec![n]
+                        } else {
+                            part.into_inner()
+                                .map(|x| {
+                                    let r = Node::from_expr(file, wd.clone(), source, x, resolver, includes);
+                                    if let Ok((n, i)) = r {
+                                        i.iter().for_each(|x| included.push(x.clone()));
+                                        Ok(n)
+                                    } else {
+                                        Err(r.err().unwrap())
+                                    }
+                                })
+                                .collect::<ResultNodeVec>()?
+                        })
+                    } else {
+                        None
+                    };
+                    match rule {
+                        Rule::ifdef => Statement::IfDef {
+                            ident,
+                            positive,
+                            negative,
+                        },
+                        Rule::ifndef => Statement::IfNDef {
+                            ident,
+                            positive,
+                            negative,
+                        },
+                        _ => Statement::If {
+                            condition: ident,
+                            positive,
+                            negative,
                         },
                     }
                 }
@@ -314,6 +380,11 @@ impl Node {
                 Rule::include_file => unimplemented!(),
                 Rule::define_whitespace => unimplemented!(),
                 Rule::conditional_block => unimplemented!(),
+                Rule::conditional_else => unimplemented!(),
+                Rule::condition => unimplemented!(),
+                Rule::eval_body => unimplemented!(),
+                Rule::enum_item => unimplemented!(),
+                Rule::enum_value => unimplemented!(),
                 Rule::COMMENT => unimplemented!(),
                 Rule::WHITESPACE => unimplemented!(),
             },
@@ -321,3 +392,16 @@ impl Node {
         Ok((node, included))
     }
 }
+
+/// Integers are 32 bit in rapified configs
+fn integer(file: &str, source: &str, pair: &pest::iterators::Pair<Rule>) -> Result<i32, ArmaLintError> {
+    pair.as_str().parse().map_err(|_| {
+        super::invalid(
+            file,
+            source,
+            pair,
+            format!("`{}` is too large for an integer", pair.as_str()),
+            "integers range from -2147483648 to 2147483647",
+        )
+    })
+}
@@ -0,0 +1,392 @@
+use super::Rule;
+use crate::config::{Code, Diagnostic, Span};
+
+/// Parsing stops after this many syntax errors
+pub(super) const MAX_ERRORS: usize = 100;
+
+/// A syntax error with a friendly message and a way to skip past it
+pub(super) struct SyntaxError {
+    message: String,
+    help: Option<String>,
+    /// Highlighted range of the source
+    span: (usize, usize),
+    /// Replacement of the highlighted range that fixes the error
+    fix: Option<String>,
+    repair: Repair,
+}
+
+enum Repair {
+    /// Replaces a range with spaces, keeping newlines so later positions don't move
+    Blank(usize, usize),
+    /// Closes a class that is still open at the end of the file
+    Append(&'static str),
+}
+
+impl SyntaxError {
+    /// Explains a pest error using the source around it
+    pub(super) fn new(source: &str, err: &pest::error::Error<Rule>) -> Self {
+        let pos = match err.location {
+            pest::error::InputLocation::Pos(pos) => pos,
+            pest::error::InputLocation::Span((start, _)) => start,
+        };
+        let positives = match &err.variant {
+            pest::error::ErrorVariant::ParsingError { positives, .. } => positives.clone(),
+            pest::error::ErrorVariant::CustomError { .. } => Vec::new(),
+        };
+        let bytes = source.as_bytes();
+        let expects_item = positives.contains(&Rule::prop);
+        let expects_end = positives.contains(&Rule::EOI);
+        let at = next_char(bytes, pos);
+
+        if at.is_none() && expects_item && !expects_end {
+            let open = unclosed(bytes).unwrap_or(0);
+            let start = line_start(bytes, open);
+            let header = source[start..open].trim();
+            return SyntaxError {
+                message: format!("missing `}};` to close `{}`", header),
+                help: None,
+                span: trimmed(source, start, open),
+                fix: None,
+                repair: Repair::Append("\n};"),
+            };
+        }
+
+        let pos = at.unwrap_or(pos);
+        if bytes.get(pos) == Some(&b'}') && expects_item {
+            if expects_end {
+                return SyntaxError {
+                    message: "unexpected `}`, there is no class to close".to_string(),
+                    help: Some("remove this `}`".to_string()),
+                    span: (pos, pos + 1),
+                    fix: Some(String::new()),
+                    repair: Repair::Blank(pos, pos + 1),
+                };
+            }
+            if next_char(bytes, pos + 1).map(|i| bytes[i]) != Some(b';') {
+                return SyntaxError {
+                    message: "missing `;` after class body".to_string(),
+                    help: Some("add `;` after the `}`".to_string()),
+                    span: (pos, pos + 1),
+                    fix: Some("};".to_string()),
+                    repair: Repair::Blank(statement_start(bytes, pos + 1), pos + 1),
+                };
+            }
+        }
+
+        let start = statement_start(bytes, pos);
+        let statement = source[start..pos].trim();
+        let end = statement_end(bytes, pos);
+        // At the end of the file the error is on the last line with content
+        let line = match at {
+            Some(_) => line_start(bytes, pos),
+            None => line_start(
+                bytes,
+                bytes[..pos].iter().rposition(|b| !b.is_ascii_whitespace()).unwrap_or(0),
+            ),
+        };
+        let (message, help) = if statement.starts_with('#') || is_directive(bytes, line) {
+            let start = if statement.starts_with('#') { start } else { line };
+            let end = directive_end(bytes, start);
+            return SyntaxError {
+                message: format!("invalid directive `{}`", source[start..end].trim()),
+                help: None,
+                span: trimmed(source, start, end),
+                fix: None,
+                repair: Repair::Blank(start, end),
+            };
+        } else if statement.starts_with("class ") && positives == [Rule::ident] {
+            if statement.ends_with(':') {
+                ("missing the name of the parent class".to_string(), None)
+            } else {
+                ("missing the name of the class".to_string(), None)
+            }
+        } else if statement.starts_with("enum") {
+            let end = statement_end(bytes, start);
+            return SyntaxError {
+                message: "invalid enum, members are `NAME` or `NAME = number`".to_string(),
+                help: None,
+                span: last_line(source, trimmed(source, start, end)),
+                fix: None,
+                repair: Repair::Blank(start, end),
+            };
+        } else if let Some(eq) = statement.find('=').filter(|_| !expects_item) {
+            let full = source[start..end].trim();
+            let first = full.find('\n').map_or(full, |i| &full[..i]);
+            if first.len() < full.len() && !first.ends_with(['{', ',', '\\']) && full[first.len()..].contains('=') {
+                // The next property starts on the following line
+                let first = trimmed(source, start, start + source[start..].find(first).unwrap() + first.len());
+                return SyntaxError {
+                    message: "missing `;` after property".to_string(),
+                    help: Some("add `;` at the end".to_string()),
+                    span: first,
+                    fix: Some(format!("{};", &source[first.0..first.1])),
+                    repair: Repair::Blank(first.0, first.1),
+                };
+            }
+            let terminated = full.ends_with(';');
+            let ident = full[..eq].trim_end_matches('+').trim();
+            let value = full[eq + 1..].trim_end_matches(';').trim();
+            if value.is_empty() {
+                (format!("missing value for `{}`", ident), None)
+            } else if value.starts_with('{') && value.matches('{').count() > value.matches('}').count() {
+                ("missing `}` to close the array".to_string(), None)
+            } else if ident.ends_with("[]") && !value.starts_with('{') {
+                (
+                    format!("expected an array for `{}`", ident),
+                    Some(format!("use `{{{}}}`", value)),
+                )
+            } else if !terminated {
+                (
+                    "missing `;` after property".to_string(),
+                    Some("add `;` at the end".to_string()),
+                )
+            } else {
+                (format!("invalid value for `{}`", ident), None)
+            }
+        } else {
+            let token = match at {
+                Some(at) => format!("`{}`", token(source, at)),
+                None => "end of file".to_string(),
+            };
+            return SyntaxError {
+                message: format!("unexpected {}, expected {}", token, expected(&positives)),
+                help: None,
+                span: match at {
+                    Some(at) => (at, at + token_len(source, at)),
+                    None => (pos, pos),
+                },
+                fix: None,
+                repair: Repair::Blank(start, end.max(pos + 1).min(bytes.len())),
+            };
+        };
+        SyntaxError {
+            message,
+            help,
+            span: last_line(source, trimmed(source, start, end)),
+            fix: None,
+            repair: Repair::Blank(start, end),
+        }
+    }
+
+    /// Changes the source so parsing can continue after the error
+    ///
+    /// Returns false when the source was not changed.
+    pub(super) fn repair(&self, source: &mut String) -> bool {
+        match self.repair {
+            Repair::Blank(start, end) => {
+                if start >= end || source[start..end].chars().all(char::is_whitespace) {
+                    return false;
+                }
+                // Multi-byte characters become several spaces to keep byte offsets
+                let blank: String = source[start..end]
+                    .chars()
+                    .map(|c| {
+                        if c == '\n' {
+                            "\n".to_string()
+                        } else {
+                            " ".repeat(c.len_utf8())
+                        }
+                    })
+                    .collect();
+                source.replace_range(start..end, &blank);
+                true
+            }
+            Repair::Append(text) => {
+                source.push_str(text);
+                true
+            }
+        }
+    }
+
+    /// Diagnostic pointing at the error
+    pub(super) fn diagnostic(&self, file: &str, source: &str) -> Diagnostic {
+        let (start, end) = self.span;
+        let end = end.min(source.len()).max(start);
+        let span = Span {
+            file: file.to_string(),
+            start: line_col(source, start),
+            end: line_col(source, end),
+        };
+        let mut diagnostic = Diagnostic::new(Code::SyntaxError, self.message.clone(), span.clone());
+        diagnostic.help = self.help.clone();
+        if let Some(fix) = &self.fix {
+            diagnostic = diagnostic.fix(span, fix.clone());
+        }
+        diagnostic
+    }
+}
+
+/// Line and column, both starting at 1
+fn line_col(source: &str, pos: usize) -> (usize, usize) {
+    let before = &source[..pos.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, col)
+}
+
+/// The next character that is not whitespace or part of a comment
+fn next_char(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b' ' | b'\t' | b'\r' | b'\n' => pos += 1,
+            b'/' if bytes.get(pos + 1) == Some(&b'/') => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                pos += 2;
+                while pos < bytes.len() && !bytes[pos..].starts_with(b"*/") {
+                    pos += 1;
+                }
+                pos += 2;
+            }
+            _ => return Some(pos),
+        }
+    }
+    None
+}
+
+/// The last character before `pos` that is not whitespace
+fn previous_char(bytes: &[u8], pos: usize) -> Option<u8> {
+    bytes[..pos].iter().rev().find(|b| !b.is_ascii_whitespace()).copied()
+}
+
+fn line_start(bytes: &[u8], pos: usize) -> usize {
+    bytes[..pos].iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1)
+}
+
+fn is_directive(bytes: &[u8], line: usize) -> bool {
+    bytes[line..].iter().find(|b| **b != b' ' && **b != b'\t') == Some(&b'#')
+}
+
+/// Whether a `{` starts an array or enum rather than a class body
+fn opens_array(bytes: &[u8], pos: usize) -> bool {
+    matches!(previous_char(bytes, pos), Some(b'=') | Some(b',') | Some(b'{'))
+        || bytes[..pos].trim_ascii_end().ends_with(b"enum")
+}
+
+/// Start of the statement containing `pos`, after the previous `;`, class body or directive
+fn statement_start(bytes: &[u8], pos: usize) -> usize {
+    let mut depth = 0;
+    let mut i = pos.min(bytes.len());
+    while i > 0 {
+        i -= 1;
+        match bytes[i] {
+            b'}' => depth += 1,
+            b'{' if depth > 0 => depth -= 1,
+            b'{' if !opens_array(bytes, i) => return i + 1,
+            b';' if depth == 0 => return i + 1,
+            b'\n' if depth == 0 && is_directive(bytes, line_start(bytes, i)) && (i == 0 || bytes[i - 1] != b'\\') => {
+                return i + 1
+            }
+            _ => {}
+        }
+    }
+    0
+}
+
+/// End of the statement containing `pos`, after its `;` or before the `}` of the class it is in
+fn statement_end(bytes: &[u8], pos: usize) -> usize {
+    let mut depth = 0;
+    for i in pos..bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' if depth > 0 => depth -= 1,
+            b'}' => return i,
+            b';' if depth == 0 => return i + 1,
+            b'\n' if depth == 0 && is_directive(bytes, i + 1) => return i,
+            _ => {}
+        }
+    }
+    bytes.len()
+}
+
+/// End of the directive starting on the line of `pos`, following `\` line continuations
+fn directive_end(bytes: &[u8], pos: usize) -> usize {
+    let mut i = pos;
+    while i < bytes.len() {
+        if bytes[i] == b'\n' && (i == 0 || bytes[i - 1] != b'\\') {
+            return i;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// The `{` of the innermost class that is never closed
+fn unclosed(bytes: &[u8]) -> Option<usize> {
+    let mut open = Vec::new();
+    for (i, b) in bytes.iter().enumerate() {
+        match b {
+            b'{' => open.push(i),
+            b'}' => {
+                open.pop();
+            }
+            _ => {}
+        }
+    }
+    open.into_iter().rev().find(|i| !opens_array(bytes, *i))
+}
+
+/// Range without the surrounding whitespace
+fn trimmed(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let text = &source[start..end];
+    let start = start + (text.len() - text.trim_start().len());
+    (start, start + text.trim().len())
+}
+
+/// The part of a range on its last line
+fn last_line(source: &str, (start, end): (usize, usize)) -> (usize, usize) {
+    let line = source[start..end].rfind('\n').map_or(start, |i| start + i + 1);
+    trimmed(source, line, end)
+}
+
+fn token(source: &str, pos: usize) -> &str {
+    &source[pos..pos + token_len(source, pos)]
+}
+
+/// Length of the word or single symbol at `pos`
+fn token_len(source: &str, pos: usize) -> usize {
+    let rest = &source[pos..];
+    let word = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+    if word > 0 {
+        word
+    } else {
+        rest.chars().next().map_or(0, char::len_utf8)
+    }
+}
+
+/// What the parser expected, in words
+fn expected(positives: &[Rule]) -> String {
+    let mut names: Vec<&str> = Vec::new();
+    for rule in positives {
+        let name = match rule {
+            Rule::EOI => "the end of the file",
+            Rule::prop | Rule::propexpand => "a property",
+            Rule::class | Rule::classextends | Rule::classdef | Rule::classdelete => "a class",
+            Rule::enumeration => "an enum",
+            Rule::enum_item => "an enum member",
+            Rule::array => "an array",
+            Rule::ident => "a name",
+            Rule::exec => "`__EXEC`",
+            Rule::include
+            | Rule::define
+            | Rule::define_flag
+            | Rule::define_macro
+            | Rule::undef
+            | Rule::ifdef
+            | Rule::ifndef
+            | Rule::ifcond => "a directive",
+            _ => "a value",
+        };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    match names.split_last() {
+        None => "something else".to_string(),
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+    }
+}
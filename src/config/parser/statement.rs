@@ -1,4 +1,4 @@
-use super::Node;
+use super::{Node, Trivia};
 
 #[derive(Debug, Clone, PartialEq, AsStaticStr)]
 pub enum Statement {
@@ -21,6 +21,7 @@ pub enum Statement {
         props: Vec<Node>,
     },
     ClassDef(Box<Node>),
+    Enum(Vec<(String, Option<i32>)>),
     ClassDelete(Box<Node>),
     Ident(String),
     IdentArray(String),
@@ -28,8 +29,16 @@ pub enum Statement {
     // Special
     FILE,
     LINE,
+    Eval(String),
+    Exec(String),
 
     // Directives
+    Include {
+        path: String,
+        /// The file that was found, its directory and name in lowercase
+        file: String,
+        content: Box<Node>,
+    },
     Define {
         ident: String,
         value: Option<Box<Node>>,
@@ -51,6 +60,25 @@ pub enum Statement {
         positive: Vec<Node>,
         negative: Option<Vec<Node>>,
     },
+    IfNDef {
+        ident: String,
+        positive: Vec<Node>,
+        negative: Option<Vec<Node>>,
+    },
+    /// `#if` and `#elif`, an `#elif` chain is nested in `negative`
+    If {
+        condition: String,
+        positive: Vec<Node>,
+        negative: Option<Vec<Node>>,
+    },
+
+    // Lossless
+    Annotated {
+        leading: Vec<Trivia>,
+        node: Box<Node>,
+        trailing: Option<String>,
+    },
+    Trivia(Vec<Trivia>),
 
     // Internal
     Processed(Box<Statement>, Box<Statement>),
@@ -62,8 +90,4 @@ pub enum Statement {
     InvalidCall(String, Box<Statement>),
     Inserted(Vec<Node>),
     Gone,
-
-    // Warnings & Errors
-    NonUppercaseDefine(Box<Statement>),
-    Redefine(String, Box<Statement>, Box<Option<Node>>),
 }
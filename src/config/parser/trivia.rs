@@ -0,0 +1,223 @@
+use super::{Node, Statement};
+
+/// Source text that does not affect the config, kept by a lossless parse
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trivia {
+    /// A line or block comment, including the `//` or `/* */`
+    Comment(String),
+    BlankLine,
+}
+
+/// Attaches the comments and blank lines of `source` to the nodes of a config
+pub fn attach_config(nodes: Vec<Node>, source: &str) -> Vec<Node> {
+    attach(nodes, source, 0, source.len())
+}
+
+/// Attaches trivia to a list of sibling nodes found between `start` and `end` in `source`
+///
+/// Comments on the same line as the end of a node are trailing, all others lead the next node.
+/// Trivia after the last node is kept in a `Statement::Trivia` node.
+fn attach(nodes: Vec<Node>, source: &str, start: usize, end: usize) -> Vec<Node> {
+    let file = match nodes.first() {
+        Some(n) => n.file.clone(),
+        None => return nodes,
+    };
+    let mut output: Vec<Node> = Vec::new();
+    let mut cursor = start;
+    for node in nodes {
+        if node.file != file || node.start.0 < cursor || node.start.0 > end {
+            output.push(node);
+            continue;
+        }
+        let (trailing, leading) = scan(&source[cursor..node.start.0], !output.is_empty(), line_start(source, cursor));
+        if let Some(comment) = trailing {
+            set_trailing(output.last_mut().unwrap(), comment);
+        }
+        cursor = node.end.0;
+        let node = attach_node(node, source);
+        output.push(if leading.is_empty() {
+            node
+        } else {
+            annotate(node, leading, None)
+        });
+    }
+    if cursor < end {
+        let (trailing, dangling) = scan(&source[cursor..end], !output.is_empty(), line_start(source, cursor));
+        if let Some(comment) = trailing {
+            set_trailing(output.last_mut().unwrap(), comment);
+        }
+        if dangling.iter().any(|t| t != &Trivia::BlankLine) {
+            output.push(Node {
+                file,
+                start: (cursor, line_col(source, cursor)),
+                end: (end, line_col(source, end)),
+                line: source[cursor..end].to_string(),
+                statement: Statement::Trivia(dangling),
+            });
+        }
+    }
+    output
+}
+
+fn attach_node(mut node: Node, source: &str) -> Node {
+    let offset = node.start.0;
+    node.statement = match node.statement {
+        Statement::Class { ident, extends, props } => {
+            let start = offset + node.line.find('{').map_or(0, |i| i + 1);
+            Statement::Class {
+                ident,
+                extends,
+                props: attach(props, source, start, node.end.0.saturating_sub(1)),
+            }
+        }
+        Statement::IfDef {
+            ident,
+            positive,
+            negative,
+        } => {
+            let (positive, negative) = attach_conditional((offset, node.end.0), &node.line, positive, negative, source);
+            Statement::IfDef {
+                ident,
+                positive,
+                negative,
+            }
+        }
+        Statement::IfNDef {
+            ident,
+            positive,
+            negative,
+        } => {
+            let (positive, negative) = attach_conditional((offset, node.end.0), &node.line, positive, negative, source);
+            Statement::IfNDef {
+                ident,
+                positive,
+                negative,
+            }
+        }
+        Statement::If {
+            condition,
+            positive,
+            negative,
+        } => {
+            let (positive, negative) = attach_conditional((offset, node.end.0), &node.line, positive, negative, source);
+            Statement::If {
+                condition,
+                positive,
+                negative,
+            }
+        }
+        statement => statement,
+    };
+    node
+}
+
+fn attach_conditional(
+    (offset, node_end): (usize, usize),
+    line: &str,
+    positive: Vec<Node>,
+    negative: Option<Vec<Node>>,
+    source: &str,
+) -> (Vec<Node>, Option<Vec<Node>>) {
+    let start = offset + line.find('\n').map_or(0, |i| i + 1);
+    let positive_end = directive_line(source, positive.last().map_or(start, |n| n.end.0)).min(node_end);
+    let positive = attach(positive, source, start, positive_end);
+    let negative = negative.map(|negative| match negative.first().map(|n| &n.statement) {
+        Some(Statement::If { .. }) => negative.into_iter().map(|n| attach_node(n, source)).collect(),
+        _ => {
+            let start = source[positive_end..]
+                .find('\n')
+                .map_or(positive_end, |i| positive_end + i + 1);
+            let end = directive_line(source, negative.last().map_or(start, |n| n.end.0)).min(node_end);
+            attach(negative, source, start, end)
+        }
+    });
+    (positive, negative)
+}
+
+/// Finds the start of the next line that begins with a directive
+fn directive_line(source: &str, from: usize) -> usize {
+    let mut position = from;
+    while let Some(i) = source[position..].find('\n') {
+        position += i + 1;
+        if source[position..].trim_start_matches(&[' ', '\t'][..]).starts_with('#') {
+            return position;
+        }
+    }
+    source.len()
+}
+
+fn line_start(source: &str, position: usize) -> bool {
+    position == 0 || source[..position].ends_with('\n')
+}
+
+fn line_col(source: &str, position: usize) -> (usize, usize) {
+    let before = &source[..position];
+    let line = before.matches('\n').count() + 1;
+    let col = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, col)
+}
+
+/// Splits the text between two nodes into a trailing comment for the previous node and leading trivia for the next
+fn scan(gap: &str, has_previous: bool, at_line_start: bool) -> (Option<String>, Vec<Trivia>) {
+    let mut trailing = None;
+    let mut leading = Vec::new();
+    let mut first_line = !at_line_start;
+    let mut line_empty = true;
+    let mut rest = gap;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("//") || rest.starts_with("/*") {
+            let len = if rest.starts_with("//") {
+                rest.find('\n').unwrap_or(rest.len())
+            } else {
+                rest.find("*/").map_or(rest.len(), |i| i + 2)
+            };
+            let comment = rest[..len].trim_end().to_string();
+            if first_line && has_previous && trailing.is_none() {
+                trailing = Some(comment);
+            } else {
+                leading.push(Trivia::Comment(comment));
+            }
+            line_empty = false;
+            rest = &rest[len..];
+        } else if c == '\n' {
+            if !first_line && line_empty {
+                leading.push(Trivia::BlankLine);
+            }
+            first_line = false;
+            line_empty = true;
+            rest = &rest[1..];
+        } else if c == '#' {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            line_empty = false;
+            rest = &rest[len..];
+        } else {
+            if !c.is_whitespace() && c != ';' {
+                line_empty = false;
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    (trailing, leading)
+}
+
+fn annotate(node: Node, leading: Vec<Trivia>, trailing: Option<String>) -> Node {
+    Node {
+        file: node.file.clone(),
+        start: node.start,
+        end: node.end,
+        line: node.line.clone(),
+        statement: Statement::Annotated {
+            leading,
+            node: Box::new(node),
+            trailing,
+        },
+    }
+}
+
+fn set_trailing(node: &mut Node, comment: String) {
+    if let Statement::Annotated { trailing, .. } = &mut node.statement {
+        *trailing = Some(comment);
+    } else {
+        *node = annotate(node.clone(), Vec::new(), Some(comment));
+    }
+}
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use super::{Node, Report, Statement, AST};
+use super::exec::Executor;
+use super::{Code, Diagnostic, Node, Report, Span, Statement, AST};
 use crate::ArmaLintError;
 
 type ResultNodeVec = Result<Vec<Node>, ArmaLintError>;
@@ -10,17 +11,41 @@ type ResultNodeVec = Result<Vec<Node>, ArmaLintError>;
 pub struct PreProcessor {
     defines: HashMap<String, Option<Node>>,
     macros: HashMap<String, (Vec<String>, Node)>,
+    executor: Executor,
     report: Report,
+    /// Included files, only kept when duplicate includes are reported
+    included: Option<HashSet<String>>,
 }
 impl PreProcessor {
     pub fn new() -> Self {
         Self {
             defines: HashMap::new(),
             macros: HashMap::new(),
+            executor: Executor::new(),
             report: Report::new(),
+            included: None,
         }
     }
 
+    /// Warns about files that are included more than once without an include guard
+    pub fn warn_duplicate_includes(mut self) -> Self {
+        self.included = Some(HashSet::new());
+        self
+    }
+
+    /// Defines `ident` before a config is processed, like `#define ident value`
+    ///
+    /// `ident` can take arguments as well, `QUOTE(var)` with the value `#var`.
+    pub fn define(mut self, ident: &str, value: Option<&str>) -> Result<Self, ArmaLintError> {
+        let source = match value {
+            Some(value) => format!("#define {} {}\n", ident, value),
+            None => format!("#define {}\n", ident),
+        };
+        self.process(super::parse("<predefined>", &source)?)?;
+        self.report = Report::new();
+        Ok(self)
+    }
+
     pub fn process(&mut self, ast: AST) -> Result<AST, ArmaLintError> {
         let mut ast = ast.clone();
         let config = match ast.config.statement {
@@ -29,6 +54,9 @@ impl PreProcessor {
         };
         ast.config.statement = Statement::Config(self.process_nodes(config, None)?);
         ast.processed = true;
+        for diagnostic in self.report.errors.iter_mut().chain(self.report.warnings.iter_mut()) {
+            diagnostic.locate(&ast.files);
+        }
         ast.report = Some(self.report.clone());
         Ok(ast)
     }
@@ -87,6 +115,28 @@ impl PreProcessor {
                     Box::new(node.statement),
                 );
             }
+            Statement::Eval(code) => match self.executor.eval(code) {
+                Ok(value) => {
+                    node.statement =
+                        Statement::Processed(Box::new(value.into_statement()), Box::new(node_clone.statement.clone()));
+                }
+                Err(message) => {
+                    self.report.push(Diagnostic::new(
+                        Code::InvalidEval,
+                        format!("Unable to evaluate `__EVAL`, it will be kept verbatim: {}", message),
+                        Span::in_node(&node_clone, 0, first_line(&node_clone)),
+                    ));
+                }
+            },
+            Statement::Exec(code) => {
+                if let Err(message) = self.executor.exec(code) {
+                    self.report.push(Diagnostic::new(
+                        Code::InvalidEval,
+                        format!("Unable to execute `__EXEC`: {}", message),
+                        Span::in_node(&node_clone, 0, first_line(&node_clone)),
+                    ));
+                }
+            }
             Statement::Ident(val) => {
                 if let Some(s) = self.defines.get(val) {
                     if let Some(d) = s {
@@ -111,6 +161,24 @@ impl PreProcessor {
                     }
                 }
             }
+            Statement::Enum(members) => {
+                for (name, value) in super::enum_values(members) {
+                    let mut define = node_clone.clone();
+                    define.statement = Statement::Integer(value);
+                    self.defines.insert(name, Some(define));
+                }
+            }
+            Statement::Annotated {
+                leading,
+                node: inner,
+                trailing,
+            } => {
+                node.statement = Statement::Annotated {
+                    leading: leading.to_vec(),
+                    node: Box::new(self.process_node(*inner.clone(), macro_root)?),
+                    trailing: trailing.clone(),
+                };
+            }
             Statement::ClassDef(ident) => {
                 node.statement = Statement::ClassDef(Box::new(self.process_node(*ident.clone(), macro_root)?));
             }
@@ -120,24 +188,28 @@ impl PreProcessor {
             Statement::Config(nodes) => {
                 node.statement = Statement::Config(self.process_nodes(nodes.to_vec(), macro_root.clone())?);
             }
+            Statement::Include { path, file, content } => {
+                if let Some(included) = &mut self.included {
+                    if !included.insert(file.clone()) && !guarded(content) {
+                        self.report.push(
+                            Diagnostic::new(
+                                Code::DuplicateInclude,
+                                format!("`{}` is included more than once without an include guard", path),
+                                Span::in_node(&node_clone, node_clone.line.find(path.as_str()).unwrap_or(0), path.len()),
+                            )
+                            .help("wrap the file in `#ifndef` and `#define` so it is only read once"),
+                        );
+                    }
+                }
+                node.statement = self.process_node(*content.clone(), macro_root)?.statement;
+            }
             // Directives
             Statement::Define { ident, value } => {
-                let mut warn_node = node_clone.clone();
                 if let Some(old) = self.defines.remove(ident) {
-                    warn_node.statement = Statement::Redefine(
-                        format!("Redefining `{}`", ident),
-                        Box::new(warn_node.statement.clone()),
-                        Box::new(old),
-                    );
-                    self.report.warnings.push(warn_node.clone());
+                    self.report.push(redefine(&node_clone, ident, old.as_ref()));
                 };
                 if let Some(old) = self.macros.remove(ident) {
-                    warn_node.statement = Statement::Redefine(
-                        format!("Redefining `{}`", ident),
-                        Box::new(warn_node.statement.clone()),
-                        Box::new(Some(old.1)),
-                    );
-                    self.report.warnings.push(warn_node.clone());
+                    self.report.push(redefine(&node_clone, ident, Some(&old.1)));
                 };
                 if let Some(val) = value {
                     let data = self.process_node(*val.clone(), macro_root.clone())?;
@@ -146,48 +218,37 @@ impl PreProcessor {
                     self.defines.insert(ident.to_string(), None);
                 }
                 if ident.to_uppercase() != *ident {
-                    warn_node.statement = Statement::NonUppercaseDefine(Box::new(node.statement.clone()));
-                    self.report.warnings.push(warn_node.clone());
+                    self.report.push(non_uppercase(&node_clone, ident));
                 }
             }
             Statement::DefineMacro { ident, args, value, .. } => {
-                let mut warn_node = node_clone.clone();
                 if let Some(old) = self.defines.remove(ident) {
-                    warn_node.statement = Statement::Redefine(
-                        format!("Redefining `{}`", ident),
-                        Box::new(warn_node.statement.clone()),
-                        Box::new(old),
-                    );
-                    self.report.warnings.push(warn_node.clone());
+                    self.report.push(redefine(&node_clone, ident, old.as_ref()));
                 };
                 if let Some(old) = self.macros.remove(ident) {
-                    warn_node.statement = Statement::Redefine(
-                        format!("Redefining `{}`", ident),
-                        Box::new(warn_node.statement.clone()),
-                        Box::new(Some(old.1)),
-                    );
-                    self.report.warnings.push(warn_node.clone());
+                    self.report.push(redefine(&node_clone, ident, Some(&old.1)));
                 };
                 self.macros.insert(ident.to_string(), (args.to_vec(), *value.clone()));
                 if ident.to_uppercase() != *ident {
-                    warn_node.statement = Statement::NonUppercaseDefine(Box::new(node.statement.clone()));
-                    self.report.warnings.push(warn_node.clone());
+                    self.report.push(non_uppercase(&node_clone, ident));
                 }
             }
             Statement::MacroCall { ident, args } => {
                 if let Some(mac) = self.macros.get(ident) {
                     let (mac_args, mac_node) = mac.clone();
                     if mac_args.len() != args.len() {
-                        node.statement = Statement::InvalidCall(
-                            format!(
-                                "Calling macro `{}` with `{}` args, requires `{}`",
-                                ident,
-                                args.len(),
-                                mac_args.len()
-                            ),
-                            Box::new(node.statement.clone()),
+                        let message = format!(
+                            "Calling macro `{}` with `{}` args, requires `{}`",
+                            ident,
+                            args.len(),
+                            mac_args.len()
                         );
-                        self.report.errors.push(node.clone());
+                        self.report.push(Diagnostic::new(
+                            Code::InvalidCall,
+                            message.clone(),
+                            Span::in_node(&node_clone, 0, ident.len()),
+                        ));
+                        node.statement = Statement::InvalidCall(message, Box::new(node.statement.clone()));
                     } else {
                         let old_defines = self.defines.clone();
                         for (i, val) in args.iter().enumerate() {
@@ -214,11 +275,13 @@ impl PreProcessor {
                         self.defines = old_defines;
                     }
                 } else {
-                    node.statement = Statement::Undefined(
-                        format!("Call to undefined macro `{}`", ident),
-                        Box::new(node.statement.clone()),
-                    );
-                    self.report.errors.push(node.clone());
+                    let message = format!("Call to undefined macro `{}`", ident);
+                    self.report.push(Diagnostic::new(
+                        Code::UndefinedMacro,
+                        message.clone(),
+                        Span::in_node(&node_clone, 0, ident.len()),
+                    ));
+                    node.statement = Statement::Undefined(message, Box::new(node.statement.clone()));
                 }
             }
             Statement::MacroCallArg(inner_args) => {
@@ -273,7 +336,7 @@ impl PreProcessor {
             }
             Statement::Unquoted(children) => {
                 let mut output = String::new();
-                for child in children {
+                for child in children.iter() {
                     match child.statement {
                         Statement::Char(c) => {
                             output.push(c);
@@ -311,19 +374,34 @@ impl PreProcessor {
                             )
                         }
                     } else {
-                        self.report.warnings.push(node_clone.clone());
+                        // Single characters from macros and directives such as `#IDENT` are left alone
+                        let single = node_clone.line.len() != 1 && children.len() == 1;
+                        let directive = node_clone.line.starts_with('#') && !node_clone.line.contains(' ');
+                        if !single && !directive {
+                            let span = Span::in_node(&node_clone, 0, node_clone.line.len());
+                            let quoted = format!("\"{}\"", node_clone.line);
+                            self.report.push(
+                                Diagnostic::new(Code::UnquotedValue, "Unquoted value", span.clone())
+                                    .help(format!("use `{}`", quoted))
+                                    .fix(span, quoted),
+                            );
+                        }
                         Statement::InternalStr(self.tokens(output)?)
                     }),
                     Box::new(node.statement),
                 );
             }
             Statement::Undefine(ident) => {
-                if self.macros.remove(ident).is_none() {
-                    node.statement = Statement::Undefined(
-                        format!("Attempt to undefine an undefined identifier `{}`", ident),
-                        Box::new(node.statement.clone()),
+                if self.macros.remove(ident).is_none() && self.defines.remove(ident).is_none() {
+                    self.report.push(
+                        Diagnostic::new(
+                            Code::UndefineUndefined,
+                            format!("Attempt to undefine an undefined identifier `{}`", ident),
+                            Span::in_node(&node_clone, 7, ident.len()),
+                        )
+                        .help("remove this line")
+                        .fix(Span::of_node(&node_clone), ""),
                     );
-                    self.report.warnings.push(node.clone());
                 }
             }
             Statement::IfDef {
@@ -339,6 +417,59 @@ impl PreProcessor {
                     Statement::Gone
                 };
             }
+            Statement::IfNDef {
+                ident,
+                positive,
+                negative,
+            } => {
+                node.statement = if !self.defines.contains_key(ident) && !self.macros.contains_key(ident) {
+                    Statement::Inserted(self.process_nodes(positive.to_vec(), macro_root.clone())?)
+                } else if let Some(n) = negative {
+                    Statement::Inserted(self.process_nodes(n.to_vec(), macro_root.clone())?)
+                } else {
+                    Statement::Gone
+                };
+            }
+            Statement::If {
+                condition,
+                positive,
+                negative,
+            } => match super::condition::evaluate(condition, &self.defines, &self.macros) {
+                Ok(eval) => {
+                    let span = condition_span(&node_clone, condition);
+                    self.undefined_in_condition(&eval.undefined, &span);
+                    let elif = negative.as_deref().and_then(elif);
+                    if eval.constant && (eval.value == 0 || (negative.is_some() && elif.is_none())) {
+                        self.report.push(Diagnostic::new(
+                            Code::Unreachable,
+                            if eval.value == 0 {
+                                "Unreachable branch, condition is always false"
+                            } else {
+                                "Unreachable `#else` branch, condition is always true"
+                            },
+                            span,
+                        ));
+                    }
+                    node.statement = if eval.value != 0 {
+                        if let Some(elif) = elif {
+                            self.skip_elif(elif, eval.constant);
+                        }
+                        Statement::Inserted(self.process_nodes(positive.to_vec(), macro_root.clone())?)
+                    } else if let Some(n) = negative {
+                        Statement::Inserted(self.process_nodes(n.to_vec(), macro_root.clone())?)
+                    } else {
+                        Statement::Gone
+                    };
+                }
+                Err(message) => {
+                    self.report.push(Diagnostic::new(
+                        Code::InvalidCondition,
+                        message,
+                        condition_span(&node_clone, condition),
+                    ));
+                    node.statement = Statement::Gone;
+                }
+            },
             // Ignored
             Statement::Char(_) => {}
             Statement::Defined(_, _) => {}
@@ -349,14 +480,60 @@ impl PreProcessor {
             Statement::InternalStr(_) => {}
             Statement::InvalidCall(_, _) => {}
             Statement::Processed(_, _) => {}
+            Statement::Trivia(_) => {}
             Statement::Undefined(_, _) => {}
-            // Warnings & erors
-            Statement::NonUppercaseDefine(_) => {}
-            Statement::Redefine(_, _, _) => {}
         }
         Ok(node)
     }
 
+    fn undefined_in_condition(&mut self, undefined: &[String], span: &Span) {
+        for ident in undefined {
+            self.report.push(
+                Diagnostic::new(
+                    Code::UndefinedInCondition,
+                    format!("Undefined identifier `{}` in condition evaluates to 0", ident),
+                    span.clone(),
+                )
+                .help("use `defined(...)` to check if an identifier is defined"),
+            );
+        }
+    }
+
+    /// Checks the conditions of an `#elif` chain that is skipped because an earlier branch is taken
+    ///
+    /// The branches are reported as unreachable when the taken branch has a constant condition.
+    fn skip_elif(&mut self, node: &Node, unreachable: bool) {
+        let (condition, negative) = match &node.statement {
+            Statement::If { condition, negative, .. } => (condition, negative),
+            _ => return,
+        };
+        let span = condition_span(node, condition);
+        match super::condition::evaluate(condition, &self.defines, &self.macros) {
+            Ok(eval) => self.undefined_in_condition(&eval.undefined, &span),
+            Err(message) => self
+                .report
+                .push(Diagnostic::new(Code::InvalidCondition, message, span.clone())),
+        }
+        let next = negative.as_deref().and_then(elif);
+        if unreachable {
+            self.report.push(Diagnostic::new(
+                Code::Unreachable,
+                "Unreachable `#elif` branch, an earlier condition is always true",
+                span.clone(),
+            ));
+            if negative.is_some() && next.is_none() {
+                self.report.push(Diagnostic::new(
+                    Code::Unreachable,
+                    "Unreachable `#else` branch, an earlier condition is always true",
+                    span,
+                ));
+            }
+        }
+        if let Some(next) = next {
+            self.skip_elif(next, unreachable);
+        }
+    }
+
     pub fn tokens(&self, text: String) -> Result<String, ArmaLintError> {
         let s = text.clone();
         let mut output = Vec::new();
@@ -407,3 +584,68 @@ impl PreProcessor {
 fn remove_first(s: &str) -> Option<&str> {
     s.chars().next().map(|c| &s[c.len_utf8()..])
 }
+
+/// Length of the first line of a node
+fn first_line(node: &Node) -> usize {
+    node.line.lines().next().unwrap_or("").len()
+}
+
+/// The condition of an `#if`
+/// The `#elif` that the negative branch of a condition is made of, if it is one
+fn elif(negative: &[Node]) -> Option<&Node> {
+    match negative {
+        [node] if node.line.starts_with("#elif") && matches!(node.statement, Statement::If { .. }) => Some(node),
+        _ => None,
+    }
+}
+
+fn condition_span(node: &Node, condition: &str) -> Span {
+    Span::in_node(node, node.line.find(condition).unwrap_or(0), condition.len())
+}
+
+/// Warning for a define or macro that replaces `old`, `None` when it was a flag
+fn redefine(node: &Node, ident: &str, old: Option<&Node>) -> Diagnostic {
+    let diagnostic = Diagnostic::new(
+        Code::Redefine,
+        format!("Redefining `{}`", ident),
+        Span::in_node(node, 8, ident.len()),
+    );
+    match old {
+        Some(old) => diagnostic
+            .note(format!(
+                "old value was `{}`",
+                match &old.statement {
+                    Statement::MacroBody(v) => v.clone(),
+                    Statement::Str(v) => v.clone(),
+                    _ => old.line.clone(),
+                }
+            ))
+            .label(Span::of_node(old), "previously defined here"),
+        None => diagnostic.note("was previously a flag"),
+    }
+}
+
+fn non_uppercase(node: &Node, ident: &str) -> Diagnostic {
+    let span = Span::in_node(node, 8, ident.len());
+    Diagnostic::new(
+        Code::NonUppercaseDefine,
+        "Use of non-uppercase characters in define identifier",
+        span.clone(),
+    )
+    .help(format!("use `{}`", ident.to_uppercase()))
+    .fix(span, ident.to_uppercase())
+}
+
+/// Whether an included file is wrapped in `#ifndef GUARD` and `#define GUARD`
+fn guarded(content: &Node) -> bool {
+    let nodes = match &content.statement {
+        Statement::Config(nodes) => nodes,
+        _ => return false,
+    };
+    match nodes.first().map(|n| &n.statement) {
+        Some(Statement::IfNDef { ident, positive, .. }) => {
+            matches!(positive.first().map(|n| &n.statement), Some(Statement::Define { ident: define, .. }) if define == ident)
+        }
+        _ => false,
+    }
+}
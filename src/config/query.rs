@@ -0,0 +1,309 @@
+use std::convert::Infallible;
+
+use super::simplify::{Array, ArrayElement, Class, Config, Entry};
+
+/// A class that can be searched by path, the classes of simplified and lazy configs
+///
+/// Both are searched by the same functions so names, wildcards and merged classes work the same.
+pub(super) trait Lookup: Sized {
+    type Entry: LookupEntry<Class = Self>;
+    /// Error reading the entries, a lazy class reads its body when it is first searched
+    type Error;
+
+    fn entries(&self) -> Result<&[(String, Self::Entry)], Self::Error>;
+}
+
+/// An entry of a `Lookup` class
+pub(super) trait LookupEntry {
+    type Class;
+
+    fn value(&self) -> Option<Value<'_>>;
+    fn class(&self) -> Option<&Self::Class>;
+}
+
+/// The value of a property
+pub(super) enum Value<'a> {
+    Str(&'a str),
+    Float(f32),
+    Int(i32),
+    Array(&'a Array),
+}
+
+impl<'a> Value<'a> {
+    pub(super) fn as_str(&self) -> Option<&'a str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Numbers as `f32`, integers are converted
+    pub(super) fn as_f32(&self) -> Option<f32> {
+        match self {
+            Value::Float(f) => Some(*f),
+            Value::Int(i) => Some(*i as f32),
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_array(&self) -> Option<&'a Array> {
+        match self {
+            Value::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+/// Finds an entry by its path relative to a class
+///
+/// A class that is opened again is merged like the engine does, the path is searched in every body
+/// of it and the last definition of a property wins. A class that is found is its last body, search
+/// through its parent to look in every body.
+pub(super) fn get<'a, C: Lookup>(class: &'a C, path: &str) -> Result<Option<&'a C::Entry>, C::Error> {
+    let segments = segments(path);
+    if segments.iter().any(|s| s.contains('*')) {
+        return Ok(get_all(class, path)?.into_iter().next().map(|(_, e)| e));
+    }
+    let (last, parents) = match segments.split_last() {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let mut bodies = vec![class];
+    for name in parents {
+        bodies = match definitions(&bodies, name)?.into_iter().map(|(_, e)| e.class()).collect() {
+            Some(bodies) => bodies,
+            None => return Ok(None),
+        };
+    }
+    Ok(definitions(&bodies, last)?.pop().map(|(_, e)| e))
+}
+
+/// Finds every entry matching a path relative to a class, in the order they are first defined
+pub(super) fn get_all<'a, C: Lookup>(class: &'a C, path: &str) -> Result<Vec<(String, &'a C::Entry)>, C::Error> {
+    let mut found = Vec::new();
+    collect(&[class], &segments(path), String::new(), &mut found)?;
+    Ok(found)
+}
+
+/// Finds an entry directly in a class
+pub(super) fn entry<'a, C: Lookup>(class: &'a C, name: &str) -> Result<Option<&'a C::Entry>, C::Error> {
+    Ok(definitions(&[class], name)?.pop().map(|(_, e)| e))
+}
+
+/// Every definition of a name in the bodies of a class that is still used
+///
+/// This is the last definition when it is a property, otherwise every class since the last property.
+fn definitions<'a, C: Lookup>(bodies: &[&'a C], name: &str) -> Result<Vec<(&'a String, &'a C::Entry)>, C::Error> {
+    let mut found: Vec<(&String, &C::Entry)> = Vec::new();
+    for class in bodies {
+        for (n, entry) in class.entries()? {
+            if !n.eq_ignore_ascii_case(name) {
+                continue;
+            }
+            if entry.class().is_none() || found.last().is_some_and(|(_, e)| e.class().is_none()) {
+                found.clear();
+            }
+            found.push((n, entry));
+        }
+    }
+    Ok(found)
+}
+
+fn collect<'a, C: Lookup>(
+    bodies: &[&'a C],
+    segments: &[&str],
+    prefix: String,
+    found: &mut Vec<(String, &'a C::Entry)>,
+) -> Result<(), C::Error> {
+    let (pattern, rest) = match segments.split_first() {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+    let mut names: Vec<&String> = Vec::new();
+    for class in bodies {
+        for (name, _) in class.entries()? {
+            if matches(pattern, name) && !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name);
+            }
+        }
+    }
+    for name in names {
+        let definitions = definitions(bodies, name)?;
+        let (name, entry) = *definitions.last().unwrap();
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if rest.is_empty() {
+            found.push((path, entry));
+        } else if let Some(classes) = definitions.iter().map(|(_, e)| e.class()).collect::<Option<Vec<_>>>() {
+            collect(&classes, rest, path, found)?;
+        }
+    }
+    Ok(())
+}
+
+impl Config {
+    /// Finds an entry by its path, `CfgVehicles/Car/displayName`
+    ///
+    /// Names are case-insensitive and `*` matches any part of a name, the first match is returned.
+    pub fn get(&self, path: &str) -> Option<&Entry> {
+        self.root.get(path)
+    }
+
+    /// Finds every entry matching a path, `CfgWeapons/*/magazines`
+    ///
+    /// Returns the full path of each entry along with it.
+    pub fn get_all(&self, path: &str) -> Vec<(String, &Entry)> {
+        self.root.get_all(path)
+    }
+}
+
+impl Lookup for Class {
+    type Entry = Entry;
+    type Error = Infallible;
+
+    fn entries(&self) -> Result<&[(String, Entry)], Infallible> {
+        Ok(&self.entries)
+    }
+}
+
+impl LookupEntry for Entry {
+    type Class = Class;
+
+    fn value(&self) -> Option<Value<'_>> {
+        match self {
+            Entry::Str(s) => Some(Value::Str(s)),
+            Entry::Float(f) => Some(Value::Float(*f)),
+            Entry::Int(i) => Some(Value::Int(*i)),
+            Entry::Array(a) => Some(Value::Array(a)),
+            _ => None,
+        }
+    }
+
+    fn class(&self) -> Option<&Class> {
+        self.as_class()
+    }
+}
+
+impl Class {
+    /// Finds an entry by its path relative to this class, see `get` for how classes are merged
+    pub fn get(&self, path: &str) -> Option<&Entry> {
+        get(self, path).unwrap_or_else(|never| match never {})
+    }
+
+    /// Finds every entry matching a path relative to this class, in the order they are first defined
+    pub fn get_all(&self, path: &str) -> Vec<(String, &Entry)> {
+        get_all(self, path).unwrap_or_else(|never| match never {})
+    }
+
+    /// Finds an entry directly in this class
+    pub fn entry(&self, name: &str) -> Option<&Entry> {
+        entry(self, name).unwrap_or_else(|never| match never {})
+    }
+}
+
+impl Entry {
+    pub fn as_str(&self) -> Option<&str> {
+        self.value()?.as_str()
+    }
+
+    /// Numbers as `f32`, integers are converted
+    pub fn as_f32(&self) -> Option<f32> {
+        self.value()?.as_f32()
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        self.value()?.as_i32()
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        self.value()?.as_array()
+    }
+
+    pub fn as_class(&self) -> Option<&Class> {
+        match self {
+            Entry::Class(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl ArrayElement {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ArrayElement::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Numbers as `f32`, integers are converted
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            ArrayElement::Float(f) => Some(*f),
+            ArrayElement::Int(i) => Some(*i as f32),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            ArrayElement::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        match self {
+            ArrayElement::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+pub(super) fn segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// Case-insensitive match where `*` matches any number of characters
+pub(super) fn matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    if !name.starts_with(first) {
+        return false;
+    }
+    let mut rest = &name[first.len()..];
+    let parts: Vec<&str> = parts.collect();
+    match parts.split_last() {
+        None => rest.is_empty(),
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(i) => rest = &rest[i + part.len()..],
+                    None => return false,
+                }
+            }
+            rest.len() >= last.len() && rest.ends_with(last)
+        }
+    }
+}
+
+#[test]
+fn wildcards() {
+    assert!(matches("*", "anything"));
+    assert!(matches("arifle_*", "ARIFLE_MX_F"));
+    assert!(matches("*_f", "arifle_MX_F"));
+    assert!(matches("a*mx*f", "arifle_MX_F"));
+    assert!(!matches("arifle_*_G", "arifle_MX_F"));
+    assert!(!matches("arifle", "arifle_MX_F"));
+}
@@ -1,25 +1,13 @@
-use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
-use std::iter::Sum;
+use std::collections::HashSet;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
 use super::simplify::{Array, ArrayElement, Class, Config, Entry};
+use crate::error::RapifiedError;
 use crate::io::*;
 use crate::ArmaLintError;
 
-impl ArrayElement {
-    pub fn rapified_length(&self) -> usize {
-        match self {
-            ArrayElement::Str(s) => s.len() + 2,
-            ArrayElement::Float(_f) => 5,
-            ArrayElement::Int(_i) => 5,
-            ArrayElement::Array(a) => {
-                1 + compressed_int_len(a.elements.len() as u32) + usize::sum(a.elements.iter().map(|e| e.rapified_length()))
-            }
-        }
-    }
-}
-
 impl Array {
     pub fn write_rapified<O: Write>(&self, output: &mut O) -> Result<usize, ArmaLintError> {
         let mut written = output.write_compressed_int(self.elements.len() as u32)?;
@@ -28,8 +16,8 @@ impl Array {
             match element {
                 ArrayElement::Str(s) => {
                     output.write_all(&[0])?;
-                    output.write_cstring(s)?;
-                    written += s.len() + 2;
+                    output.write_cstring_lossless(s)?;
+                    written += cstring_len(s) + 2;
                 }
                 ArrayElement::Float(f) => {
                     output.write_all(&[1])?;
@@ -52,286 +40,403 @@ impl Array {
     }
 
     pub fn read_rapified<I: Read + Seek>(input: &mut I) -> Result<Array, ArmaLintError> {
-        let num_elements: u32 = input.read_compressed_int()?;
-        let mut elements: Vec<ArrayElement> = Vec::with_capacity(num_elements as usize);
+        Reader::new(input)?.array(0)
+    }
+}
 
-        for _i in 0..num_elements {
-            let element_type: u8 = input.bytes().next().unwrap()?;
+impl Class {
+    /// Writes the body of the class followed by the bodies of its classes, `offset` is where it starts in the file
+    pub fn write_rapified<O: Write>(&self, output: &mut O, offset: usize) -> Result<usize, ArmaLintError> {
+        let mut buffer = Vec::new();
+        self.write_body(&mut buffer, offset)?;
+        output.write_all(&buffer)?;
+        Ok(buffer.len())
+    }
 
-            if element_type == 0 {
-                elements.push(ArrayElement::Str(input.read_cstring()?));
-            } else if element_type == 1 {
-                elements.push(ArrayElement::Float(input.read_f32::<LittleEndian>()?));
-            } else if element_type == 2 {
-                elements.push(ArrayElement::Int(input.read_i32::<LittleEndian>()?));
-            } else if element_type == 3 {
-                elements.push(ArrayElement::Array(Array::read_rapified(input)?));
-            } else {
-                return Err(ArmaLintError::InvalidInput(format!(
-                    "Unrecognized array element type: {}",
-                    element_type
-                )));
+    /// Appends the body to a buffer that starts at `start` in the file
+    ///
+    /// Class entries get a placeholder offset that is filled in once the body of the class is
+    /// appended after the entries, so the config is written in a single pass.
+    fn write_body(&self, buffer: &mut Vec<u8>, start: usize) -> Result<(), ArmaLintError> {
+        // External and deleted classes have no body, only their entry in the parent
+        if self.external || self.deletion {
+            return Ok(());
+        }
+        buffer.write_cstring_lossless(&self.parent)?;
+        buffer.write_compressed_int(self.entries.len() as u32)?;
+
+        let mut placeholders: Vec<(usize, &Class)> = Vec::new();
+        for (name, entry) in &self.entries {
+            match entry {
+                Entry::Str(s) => {
+                    buffer.write_all(&[1, 0])?;
+                    buffer.write_cstring_lossless(name)?;
+                    buffer.write_cstring_lossless(s)?;
+                }
+                Entry::Float(f) => {
+                    buffer.write_all(&[1, 1])?;
+                    buffer.write_cstring_lossless(name)?;
+                    buffer.write_f32::<LittleEndian>(*f)?;
+                }
+                Entry::Int(i) => {
+                    buffer.write_all(&[1, 2])?;
+                    buffer.write_cstring_lossless(name)?;
+                    buffer.write_i32::<LittleEndian>(*i)?;
+                }
+                Entry::Array(a) => {
+                    buffer.write_all(if a.expand { &[5] } else { &[2] })?;
+                    if a.expand {
+                        buffer.write_all(&[1, 0, 0, 0])?;
+                    }
+                    buffer.write_cstring_lossless(name)?;
+                    a.write_rapified(buffer)?;
+                }
+                Entry::Class(c) => {
+                    if c.external || c.deletion {
+                        buffer.write_all(if c.deletion { &[4] } else { &[3] })?;
+                        buffer.write_cstring_lossless(name)?;
+                    } else {
+                        buffer.write_all(&[0])?;
+                        buffer.write_cstring_lossless(name)?;
+                        placeholders.push((buffer.len(), c));
+                        buffer.write_u32::<LittleEndian>(0)?;
+                    }
+                }
+                Entry::Invisible(_) => {}
             }
         }
 
-        Ok(Array { expand: false, elements })
+        for (position, class) in placeholders {
+            let offset = (start + buffer.len()) as u32;
+            buffer[position..position + 4].copy_from_slice(&offset.to_le_bytes());
+            class.write_body(buffer, start)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the root class at level 0, otherwise the body offset of a class at the current position
+    pub fn read_rapified<I: Read + Seek>(input: &mut I, level: u32) -> Result<Class, ArmaLintError> {
+        let mut reader = Reader::new(input)?;
+        if level == 0 {
+            reader.root()
+        } else {
+            reader.class()
+        }
     }
 }
 
-impl Entry {
-    // without the name
-    pub fn rapified_length(&self) -> usize {
-        match self {
-            Entry::Str(s) => s.len() + 3,
-            Entry::Float(_f) => 6,
-            Entry::Int(_i) => 6,
-            Entry::Array(a) => {
-                let len = 1
-                    + compressed_int_len(a.elements.len() as u32)
-                    + usize::sum(a.elements.iter().map(|e| e.rapified_length()));
-                if a.expand {
-                    len + 4
-                } else {
-                    len
-                }
-            }
-            Entry::Class(c) => {
-                if c.external || c.deletion {
-                    1
-                } else {
-                    5
-                }
-            }
-            Entry::Invisible(_) => 0,
+impl Config {
+    /// Writes the rapified config to the output.
+    pub fn write_rapified<O: Write>(&self, output: &mut O) -> Result<(), ArmaLintError> {
+        output.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns the rapified config as a `Cursor`.
+    pub fn to_cursor(&self) -> Result<Cursor<Box<[u8]>>, ArmaLintError> {
+        Ok(Cursor::new(self.to_bytes()?.into_boxed_slice()))
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, ArmaLintError> {
+        let mut buffer = Vec::new();
+        buffer.write_all(b"\0raP")?;
+        buffer.write_all(b"\0\0\0\0\x08\0\0\0")?; // always_0, always_8
+        buffer.write_u32::<LittleEndian>(0)?; // enum offset, filled in after the classes
+
+        self.root.write_body(&mut buffer, 0)?;
+
+        let enum_offset = buffer.len() as u32;
+        buffer[12..16].copy_from_slice(&enum_offset.to_le_bytes());
+
+        buffer.write_u32::<LittleEndian>(self.enums.len() as u32)?;
+        for (name, value) in &self.enums {
+            buffer.write_cstring_lossless(name)?;
+            buffer.write_i32::<LittleEndian>(*value)?;
         }
+        Ok(buffer)
+    }
+
+    /// Reads the rapified config from input.
+    ///
+    /// Offsets, counts and types are checked against the size of the input, a damaged or
+    /// malicious file returns an `ArmaLintError::Rapified` instead of panicking or looping.
+    pub fn read_rapified<I: Read + Seek>(input: &mut I) -> Result<Config, ArmaLintError> {
+        let mut buffered = BufReader::new(input);
+        let mut reader = Reader::new(&mut buffered)?;
+        let enum_offset = reader.header()?;
+        let root = reader.root()?;
+        let enums = reader.enums(enum_offset)?;
+        Ok(Config { root, enums })
     }
 }
 
-impl Class {
-    pub fn rapified_length(&self) -> usize {
-        if !self.entries.is_empty() {
-            self.parent.len()
-                + 1
-                + compressed_int_len(self.entries.len() as u32)
-                + usize::sum(self.entries.iter().map(|(k, v)| {
-                    k.len()
-                        + 1
-                        + v.rapified_length()
-                        + match v {
-                            Entry::Class(c) => c.rapified_length(),
-                            _ => 0,
-                        }
-                }))
-        } else {
-            0
+/// Deepest nesting of classes and arrays that is read, deeper files are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 256;
+
+/// Start of the root class body, after the header
+pub(crate) const ROOT_OFFSET: u32 = 16;
+
+/// Reads rapified data, checking every offset, count and type against the size of the input
+pub(crate) struct Reader<'a, I: Read + Seek> {
+    input: &'a mut I,
+    len: u64,
+    /// Body offsets of the classes currently being read
+    pub(crate) stack: Vec<u32>,
+    /// Body offsets of every class that has been read
+    visited: HashSet<u32>,
+}
+
+impl<'a, I: Read + Seek> Reader<'a, I> {
+    pub(crate) fn new(input: &'a mut I) -> Result<Self, ArmaLintError> {
+        let position = input.stream_position()?;
+        let len = input.seek(SeekFrom::End(0))?;
+        input.seek(SeekFrom::Start(position))?;
+        Ok(Self::with_len(input, len))
+    }
+
+    /// Reader for an input of a known length
+    pub(crate) fn with_len(input: &'a mut I, len: u64) -> Self {
+        Self {
+            input,
+            len,
+            stack: Vec::new(),
+            visited: HashSet::new(),
         }
     }
 
-    pub fn write_rapified<O: Write>(&self, output: &mut O, offset: usize) -> Result<usize, ArmaLintError> {
-        let mut written = 0;
-
-        if !self.entries.is_empty() {
-            output.write_cstring(&self.parent)?;
-            written += self.parent.len() + 1;
-
-            written += output.write_compressed_int(self.entries.len() as u32)?;
-
-            let entries_len = usize::sum(self.entries.iter().map(|(k, v)| k.len() + 1 + v.rapified_length()));
-            let mut class_offset = offset + written + entries_len;
-            let mut class_bodies: Vec<Cursor<Box<[u8]>>> = Vec::new();
-            let pre_entries = written;
-
-            for (name, entry) in &self.entries {
-                let pre_write = written;
-                match entry {
-                    Entry::Str(s) => {
-                        output.write_all(&[1, 0])?;
-                        output.write_cstring(name)?;
-                        output.write_cstring(s)?;
-                        written += name.len() + s.len() + 4;
-                    }
-                    Entry::Float(f) => {
-                        output.write_all(&[1, 1])?;
-                        output.write_cstring(name)?;
-                        output.write_f32::<LittleEndian>(*f)?;
-                        written += name.len() + 7;
-                    }
-                    Entry::Int(i) => {
-                        output.write_all(&[1, 2])?;
-                        output.write_cstring(name)?;
-                        output.write_i32::<LittleEndian>(*i)?;
-                        written += name.len() + 7;
-                    }
-                    Entry::Array(a) => {
-                        output.write_all(if a.expand { &[5] } else { &[2] })?;
-                        if a.expand {
-                            output.write_all(&[1, 0, 0, 0])?;
-                            written += 4;
-                        }
-                        output.write_cstring(name)?;
-                        written += name.len() + 2 + a.write_rapified(output)?;
-                    }
-                    Entry::Class(c) => {
-                        if c.external || c.deletion {
-                            output.write_all(if c.deletion { &[4] } else { &[3] })?;
-                            output.write_cstring(name)?;
-                            written += name.len() + 2;
-                        } else {
-                            output.write_all(&[0])?;
-                            output.write_cstring(name)?;
-                            output.write_u32::<LittleEndian>(class_offset as u32)?;
-                            written += name.len() + 6;
-
-                            let buffer: Box<[u8]> = vec![0; c.rapified_length()].into_boxed_slice();
-                            let mut cursor: Cursor<Box<[u8]>> = Cursor::new(buffer);
-                            class_offset += c.write_rapified(&mut cursor, class_offset)?;
-                            class_bodies.push(cursor);
-                        }
-                    }
-                    Entry::Invisible(_) => {}
-                }
-                assert_eq!(written - pre_write, entry.rapified_length() + name.len() + 1);
-            }
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn position(&mut self) -> Result<u64, ArmaLintError> {
+        Ok(self.input.stream_position()?)
+    }
+
+    pub(crate) fn seek(&mut self, offset: u64) -> Result<(), ArmaLintError> {
+        self.input.seek(SeekFrom::Start(offset))?;
+        Ok(())
+    }
+
+    /// Runs a read, reporting where it failed
+    fn read<T, F: FnOnce(&mut I) -> std::io::Result<T>>(&mut self, read: F) -> Result<T, ArmaLintError> {
+        let offset = self.position()?;
+        read(self.input).map_err(|err| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => RapifiedError::UnexpectedEnd { offset }.into(),
+            std::io::ErrorKind::InvalidData => RapifiedError::InvalidCompressedInt { offset }.into(),
+            _ => err.into(),
+        })
+    }
+
+    fn u8(&mut self) -> Result<u8, ArmaLintError> {
+        self.read(|i| i.read_u8())
+    }
+
+    /// Reads a count stored as a `u32`, each counted item takes at least `size` bytes of the rest of the input
+    fn read_u32_count(&mut self, size: u64) -> Result<u32, ArmaLintError> {
+        let offset = self.position()?;
+        let count = self.read(|i| i.read_u32::<LittleEndian>())?;
+        self.check_count(offset, count, size)
+    }
+
+    /// Reads a count stored as a compressed integer, each counted item takes at least `size` bytes
+    /// of the rest of the input
+    fn read_compressed_count(&mut self, size: u64) -> Result<u32, ArmaLintError> {
+        let offset = self.position()?;
+        let count = self.read(|i| i.read_compressed_int())?;
+        self.check_count(offset, count, size)
+    }
+
+    /// Fails when `count` items of `size` bytes don't fit in the rest of the input
+    fn check_count(&mut self, offset: u64, count: u32, size: u64) -> Result<u32, ArmaLintError> {
+        let remaining = self.len.saturating_sub(self.position()?);
+        if u64::from(count) * size > remaining {
+            return Err(RapifiedError::InvalidCount { offset, count }.into());
+        }
+        Ok(count)
+    }
+
+    /// Checks that an offset read at `offset` points inside the input
+    fn offset(&self, offset: u64, target: u32) -> Result<(), ArmaLintError> {
+        if target < ROOT_OFFSET || u64::from(target) >= self.len {
+            return Err(RapifiedError::InvalidOffset { offset, target }.into());
+        }
+        Ok(())
+    }
 
-            assert_eq!(written - pre_entries, entries_len);
+    /// Checks the signature and returns the offset of the enums
+    pub(crate) fn header(&mut self) -> Result<u32, ArmaLintError> {
+        // Offsets in the file are from its start
+        self.seek(0)?;
+        let mut buffer = [0; 4];
+        self.read(|i| i.read_exact(&mut buffer))?;
+        if &buffer != b"\0raP" {
+            return Err(RapifiedError::InvalidMagic.into());
+        }
+        self.seek(12)?;
+        self.read(|i| i.read_u32::<LittleEndian>())
+    }
 
-            for cursor in class_bodies {
-                output.write_all(cursor.get_ref())?;
-                written += cursor.get_ref().len();
+    pub(crate) fn enums(&mut self, enum_offset: u32) -> Result<Vec<(String, i32)>, ArmaLintError> {
+        let mut enums = Vec::new();
+        // Configs without enums can leave the offset empty
+        if enum_offset != 0 {
+            self.offset(12, enum_offset)?;
+            self.seek(enum_offset.into())?;
+            let num_enums = self.read_u32_count(5)?;
+            enums.reserve(num_enums as usize);
+            for _ in 0..num_enums {
+                let name = self.read(|i| i.read_cstring())?;
+                enums.push((name, self.read(|i| i.read_i32::<LittleEndian>())?));
             }
         }
+        Ok(enums)
+    }
 
-        Ok(written)
+    fn root(&mut self) -> Result<Class, ArmaLintError> {
+        self.seek(ROOT_OFFSET.into())?;
+        self.visited.insert(ROOT_OFFSET);
+        self.stack.push(ROOT_OFFSET);
+        let root = self.class_body()?;
+        self.stack.pop();
+        Ok(root)
     }
 
-    pub fn read_rapified<I: Read + Seek>(input: &mut I, level: u32) -> Result<Class, ArmaLintError> {
-        let mut fp = 0;
-        if level == 0 {
-            input.seek(SeekFrom::Start(16))?;
-        } else {
-            let classbody_fp: u32 = input.read_u32::<LittleEndian>()?;
+    /// Reads the body offset of a class entry, it has to point inside the input and not at a class being read
+    ///
+    /// Returns where the offset was read and the offset.
+    pub(crate) fn class_offset(&mut self) -> Result<(u64, u32), ArmaLintError> {
+        let offset = self.position()?;
+        let target = self.read(|i| i.read_u32::<LittleEndian>())?;
+        self.offset(offset, target)?;
+        if self.stack.contains(&target) {
+            return Err(RapifiedError::CyclicClassBody { offset, target }.into());
+        }
+        if self.stack.len() >= MAX_DEPTH {
+            return Err(RapifiedError::TooDeep { offset }.into());
+        }
+        Ok((offset, target))
+    }
 
-            fp = input.seek(SeekFrom::Current(0))?;
-            input.seek(SeekFrom::Start(classbody_fp.into()))?;
+    /// Reads the body offset at the current position and the class body it points to
+    fn class(&mut self) -> Result<Class, ArmaLintError> {
+        let (offset, target) = self.class_offset()?;
+        if !self.visited.insert(target) {
+            return Err(RapifiedError::SharedClassBody { offset, target }.into());
         }
 
-        let parent = input.read_cstring()?;
-        let num_entries: u32 = input.read_compressed_int()?;
-        let mut entries: Vec<(String, Entry)> = Vec::with_capacity(num_entries as usize);
+        let fp = self.position()?;
+        self.seek(target.into())?;
+        self.stack.push(target);
+        let class = self.class_body()?;
+        self.stack.pop();
+        self.seek(fp)?;
+        Ok(class)
+    }
+
+    fn class_body(&mut self) -> Result<Class, ArmaLintError> {
+        let (parent, entries) = self.body(|reader| reader.class().map(Entry::Class))?;
+        Ok(Class {
+            parent,
+            external: false,
+            deletion: false,
+            entries,
+        })
+    }
+
+    /// Reads the parent and the entries of the class body at the current position
+    ///
+    /// Class entries with a body are read by `class`, starting at their body offset.
+    pub(crate) fn body<E, F>(&mut self, mut class: F) -> Result<(String, Vec<(String, E)>), ArmaLintError>
+    where
+        E: From<Entry>,
+        F: FnMut(&mut Self) -> Result<E, ArmaLintError>,
+    {
+        let parent = self.read(|i| i.read_cstring())?;
+        // The smallest entry is a type and an empty name
+        let num_entries = self.read_compressed_count(2)?;
+        let mut entries: Vec<(String, E)> = Vec::with_capacity(num_entries as usize);
 
         for _i in 0..num_entries {
-            let entry_type: u8 = input.bytes().next().unwrap()?;
+            let offset = self.position()?;
+            let entry_type = self.u8()?;
 
             if entry_type == 0 {
-                let name = input.read_cstring()?;
+                let name = self.read(|i| i.read_cstring())?;
 
-                let class_entry = Class::read_rapified(input, level + 1)?;
-                entries.push((name, Entry::Class(class_entry)));
+                let class_entry = class(self)?;
+                entries.push((name, class_entry));
             } else if entry_type == 1 {
-                let subtype: u8 = input.bytes().next().unwrap()?;
-                let name = input.read_cstring()?;
+                let subtype_offset = self.position()?;
+                let subtype = self.u8()?;
+                let name = self.read(|i| i.read_cstring())?;
 
                 if subtype == 0 {
-                    entries.push((name, Entry::Str(input.read_cstring()?)));
+                    entries.push((name, Entry::Str(self.read(|i| i.read_cstring())?).into()));
                 } else if subtype == 1 {
-                    entries.push((name, Entry::Float(input.read_f32::<LittleEndian>()?)));
+                    entries.push((name, Entry::Float(self.read(|i| i.read_f32::<LittleEndian>())?).into()));
                 } else if subtype == 2 {
-                    entries.push((name, Entry::Int(input.read_i32::<LittleEndian>()?)));
+                    entries.push((name, Entry::Int(self.read(|i| i.read_i32::<LittleEndian>())?).into()));
                 } else {
-                    return Err(ArmaLintError::InvalidInput(format!(
-                        "Unrecognized variable entry subtype: {}.",
-                        subtype
-                    )));
+                    return Err(RapifiedError::InvalidSubtype {
+                        offset: subtype_offset,
+                        subtype,
+                    }
+                    .into());
                 }
             } else if entry_type == 2 || entry_type == 5 {
                 if entry_type == 5 {
-                    input.seek(SeekFrom::Current(4))?;
+                    self.read(|i| i.read_u32::<LittleEndian>())?;
                 }
 
-                let name = input.read_cstring()?;
-                let mut array = Array::read_rapified(input)?;
+                let name = self.read(|i| i.read_cstring())?;
+                let mut array = self.array(0)?;
                 array.expand = entry_type == 5;
 
-                entries.push((name.clone(), Entry::Array(array)));
+                entries.push((name, Entry::Array(array).into()));
             } else if entry_type == 3 || entry_type == 4 {
-                let name = input.read_cstring()?;
+                let name = self.read(|i| i.read_cstring())?;
                 let class_entry = Class {
                     parent: String::from(""),
                     external: entry_type == 3,
-                    deletion: entry_type == 5,
+                    deletion: entry_type == 4,
                     entries: Vec::new(),
                 };
 
-                entries.push((name.clone(), Entry::Class(class_entry)));
+                entries.push((name, Entry::Class(class_entry).into()));
             } else {
-                return Err(ArmaLintError::InvalidInput(format!(
-                    "Unrecognized class entry type: {}.",
-                    entry_type
-                )));
+                return Err(RapifiedError::InvalidEntryType { offset, entry_type }.into());
             }
         }
 
-        if level > 0 {
-            input.seek(SeekFrom::Start(fp))?;
-        }
-
-        Ok(Class {
-            parent,
-            external: false,
-            deletion: false,
-            entries,
-        })
-    }
-}
-
-impl Config {
-    /// Writes the rapified config to the output.
-    pub fn write_rapified<O: Write>(&self, output: &mut O) -> Result<(), ArmaLintError> {
-        let mut writer = BufWriter::new(output);
-
-        writer.write_all(b"\0raP")?;
-        writer.write_all(b"\0\0\0\0\x08\0\0\0")?; // always_0, always_8
-
-        let buffer: Box<[u8]> = vec![0; self.root.rapified_length()].into_boxed_slice();
-        let mut cursor: Cursor<Box<[u8]>> = Cursor::new(buffer);
-        self.root.write_rapified(&mut cursor, 16)?;
-
-        let enum_offset: u32 = 16 + cursor.get_ref().len() as u32;
-        writer.write_u32::<LittleEndian>(enum_offset)?;
-
-        writer.write_all(cursor.get_ref())?;
-
-        writer.write_all(b"\0\0\0\0")?;
-
-        Ok(())
-    }
-
-    /// Returns the rapified config as a `Cursor`.
-    pub fn to_cursor(&self) -> Result<Cursor<Box<[u8]>>, ArmaLintError> {
-        let len = self.root.rapified_length() + 20;
-
-        let buffer: Box<[u8]> = vec![0; len].into_boxed_slice();
-        let mut cursor: Cursor<Box<[u8]>> = Cursor::new(buffer);
-        self.write_rapified(&mut cursor)?;
-
-        Ok(cursor)
+        Ok((parent, entries))
     }
 
-    /// Reads the rapified config from input.
-    pub fn read_rapified<I: Read + Seek>(input: &mut I) -> Result<Config, ArmaLintError> {
-        let mut reader = BufReader::new(input);
+    fn array(&mut self, depth: usize) -> Result<Array, ArmaLintError> {
+        if depth >= MAX_DEPTH {
+            let offset = self.position()?;
+            return Err(RapifiedError::TooDeep { offset }.into());
+        }
+        // The smallest element is a type and an empty string
+        let num_elements = self.read_compressed_count(2)?;
+        let mut elements: Vec<ArrayElement> = Vec::with_capacity(num_elements as usize);
 
-        let mut buffer = [0; 4];
-        reader.read_exact(&mut buffer)?;
+        for _i in 0..num_elements {
+            let offset = self.position()?;
+            let element_type = self.u8()?;
 
-        if &buffer != b"\0raP" {
-            return Err(ArmaLintError::InvalidInput(
-                "File doesn't seem to be a rapified config.".to_string(),
-            ));
+            if element_type == 0 {
+                elements.push(ArrayElement::Str(self.read(|i| i.read_cstring())?));
+            } else if element_type == 1 {
+                elements.push(ArrayElement::Float(self.read(|i| i.read_f32::<LittleEndian>())?));
+            } else if element_type == 2 {
+                elements.push(ArrayElement::Int(self.read(|i| i.read_i32::<LittleEndian>())?));
+            } else if element_type == 3 {
+                elements.push(ArrayElement::Array(self.array(depth + 1)?));
+            } else {
+                return Err(RapifiedError::InvalidArrayElement { offset, element_type }.into());
+            }
         }
 
-        Ok(Config {
-            root: Class::read_rapified(&mut reader, 0)?,
-        })
+        Ok(Array { expand: false, elements })
     }
 }
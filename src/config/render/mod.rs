@@ -1,36 +1,143 @@
-use super::{Node, Statement, AST};
+use super::simplify::{ArrayElement, Class, Config, Entry};
+use super::{Node, Statement, Trivia, AST};
 use crate::ArmaLintError;
 
 mod options;
 pub use options::{BracketStyle, IndentationType, RenderOptions};
 
 /// Renders processed AST or simplified configs
+///
+/// An AST that has not been processed is rendered as source, keeping its preprocessor commands as written
 #[derive(Clone, Copy, Hash)]
 pub struct Renderer {
     options: RenderOptions,
+    source: bool,
 }
 
 impl Renderer {
     /// Create a new Renderer with the passed options
     pub fn new(options: RenderOptions) -> Self {
-        Self { options }
+        Self { options, source: false }
     }
 
     /// Render the supplied AST
     pub fn render(self, ast: AST) -> Result<String, ArmaLintError> {
+        let renderer = Self {
+            source: !ast.processed,
+            ..self
+        };
         let mut output = String::new();
         let config = match ast.config.statement {
             Statement::Config(c) => c,
             _ => return Err(ArmaLintError::NotRoot),
         };
-        output.push_str(&self.render_nodes(config, 0)?);
+        output.push_str(&renderer.render_nodes(config, 0)?);
         Ok(output.trim().to_string())
     }
 
+    /// Render a simplified config, such as one read from a rapified file
+    pub fn render_config(self, config: &Config) -> Result<String, ArmaLintError> {
+        let mut nodes = Vec::new();
+        if !config.enums.is_empty() {
+            nodes.push(node(Statement::Enum(
+                config
+                    .enums
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Some(*value)))
+                    .collect(),
+            )));
+        }
+        nodes.append(&mut class_nodes(&config.root));
+        let renderer = Self { source: false, ..self };
+        Ok(renderer.render_nodes(nodes, 0)?.trim().to_string())
+    }
+
     pub fn render_nodes(self, nodes: Vec<Node>, indent: u8) -> Result<String, ArmaLintError> {
         let mut output = String::new();
+        let mut previous_class = None;
         for node in nodes {
-            output.push_str(&self.render_node(node, indent)?);
+            let class = is_class(&node.statement);
+            let rendered = self.render_item(node, indent)?;
+            if rendered.is_empty() {
+                continue;
+            }
+            if self.options.blank_line_between_classes
+                && previous_class.is_some_and(|previous| previous || class)
+                && !output.ends_with("\n\n")
+                && !rendered.starts_with('\n')
+            {
+                output.push('\n');
+            }
+            output.push_str(&rendered);
+            previous_class = Some(class);
+        }
+        Ok(output)
+    }
+
+    /// Renders a node that is an item of a config, class or conditional block
+    fn render_item(self, node: Node, indent: u8) -> Result<String, ArmaLintError> {
+        if !self.source {
+            return self.render_node(node, indent);
+        }
+        Ok(match node.statement {
+            Statement::Include { .. }
+            | Statement::Define { .. }
+            | Statement::DefineMacro { .. }
+            | Statement::Undefine(_) => format!("{}{}\n", self.indent(indent), node.line.trim_end()),
+            Statement::MacroCall { .. } | Statement::Ident(_) => {
+                format!("{}{};\n", self.indent(indent), self.render_statement(node.statement, indent)?)
+            }
+            _ => self.render_node(node, indent)?,
+        })
+    }
+
+    fn render_conditional(
+        self,
+        directive: String,
+        positive: Vec<Node>,
+        negative: Option<Vec<Node>>,
+        indent: u8,
+        endif: bool,
+    ) -> Result<String, ArmaLintError> {
+        let mut output = String::new();
+        output.push_str(&self.indent(indent));
+        output.push_str(&directive);
+        output.push('\n');
+        output.push_str(&self.render_nodes(positive, indent)?);
+        if let Some(mut negative) = negative {
+            let elif = negative.len() == 1 && matches!(negative[0].statement, Statement::If { .. });
+            match negative.pop().map(|n| n.statement) {
+                Some(Statement::If {
+                    condition,
+                    positive,
+                    negative,
+                }) if elif => {
+                    output.push_str(&self.render_conditional(
+                        format!("#elif {}", condition),
+                        positive,
+                        negative,
+                        indent,
+                        false,
+                    )?);
+                }
+                Some(statement) => {
+                    output.push_str(&self.indent(indent));
+                    output.push_str("#else\n");
+                    negative.push(Node {
+                        file: String::new(),
+                        start: (0, (0, 0)),
+                        end: (0, (0, 0)),
+                        line: String::new(),
+                        statement,
+                    });
+                    output.push_str(&self.render_nodes(negative, indent)?);
+                }
+                None => {}
+            }
+        }
+        if endif {
+            output.push_str(&self.indent(indent));
+            output.push_str("#endif\n");
         }
         Ok(output)
     }
@@ -46,27 +153,42 @@ impl Renderer {
         match statement {
             Statement::Property { ident, value, expand } => {
                 output.push_str(&self.indent(indent));
-                output.push_str(&format!(
-                    "{} {} {};\n",
-                    self.render_node(*ident, indent)?,
-                    if expand { "+=" } else { "=" },
-                    self.render_statement(value.statement, indent)?
-                ));
+                let operator = if expand { "+=" } else { "=" };
+                let assignment = if self.options.spaces_around_equals {
+                    format!("{} {} ", self.render_node(*ident, indent)?, operator)
+                } else {
+                    format!("{}{}", self.render_node(*ident, indent)?, operator)
+                };
+                let width = self.indent_width(indent) + assignment.len();
+                output.push_str(&assignment);
+                match array_elements(value.statement) {
+                    Ok(elements) => output.push_str(&self.render_array(elements, indent, width + 1)?),
+                    Err(statement) => output.push_str(&self.render_statement(statement, indent)?),
+                }
+                output.push_str(";\n");
             }
             Statement::Ident(val) => output.push_str(&val.to_string()),
             Statement::IdentArray(val) => output.push_str(&format!("{}[]", val.to_string())),
             Statement::Bool(val) => output.push_str(&val.to_string()),
             Statement::Str(val) => output.push_str(&format!("\"{}\"", val.replace('"', "\"\""))),
             Statement::Integer(val) => output.push_str(&val.to_string()),
-            Statement::Float(val) => output.push_str(&val.to_string()),
+            Statement::Float(val) => output.push_str(&float(val)),
             Statement::Char(val) => output.push(val),
             Statement::InternalStr(val) => output.push_str(&val.to_string()),
             Statement::Class { ident, extends, props } => {
                 output.push_str(&self.indent(indent));
                 output.push_str(&format!("class {}", self.render_node(*ident, indent)?));
                 if let Some(extended) = extends {
+                    if self.options.space_before_colon {
+                        output.push(' ');
+                    }
                     output.push_str(&format!(": {}", self.render_node(*extended, indent)?));
                 }
+                let body = self.render_nodes(props, indent + 1)?;
+                if body.is_empty() {
+                    output.push_str(" {};\n");
+                    return Ok(output);
+                }
                 match self.options.bracket_style {
                     BracketStyle::Allman => {
                         output.push_str("\n");
@@ -74,33 +196,131 @@ impl Renderer {
                     }
                     BracketStyle::Linux => output.push_str(" "),
                 }
-                output.push_str(if props.is_empty() { "{" } else { "{\n" });
-                output.push_str(&self.render_nodes(props, indent + 1)?);
+                output.push_str("{\n");
+                output.push_str(&body);
                 output.push_str(&self.indent(indent));
                 output.push_str("};\n");
             }
-            Statement::ClassDef(ident) => output.push_str(&format!("class {};\n", self.render_node(*ident, indent)?)),
-            Statement::ClassDelete(ident) => output.push_str(&format!("delete {};\n", self.render_node(*ident, indent)?)),
-            Statement::Config(nodes) => output.push_str(&self.render_nodes(nodes, indent)?),
-            Statement::Array(nodes) => {
-                output.push('{');
+            Statement::Eval(code) => output.push_str(&format!("__EVAL({})", code)),
+            Statement::Exec(code) => {
+                output.push_str(&self.indent(indent));
+                output.push_str(&format!("__EXEC({})\n", code));
+            }
+            Statement::Enum(members) => {
+                output.push_str(&self.indent(indent));
+                output.push_str("enum");
+                match self.options.bracket_style {
+                    BracketStyle::Allman => {
+                        output.push('\n');
+                        output.push_str(&self.indent(indent));
+                    }
+                    BracketStyle::Linux => output.push(' '),
+                }
+                output.push_str("{\n");
                 output.push_str(
-                    &nodes
+                    &members
                         .iter()
-                        .map(|x| self.render_statement(x.statement.clone(), indent))
-                        .collect::<Result<Vec<String>, ArmaLintError>>()?
-                        .join(", "),
+                        .map(|(name, value)| {
+                            let mut member = self.indent(indent + 1);
+                            member.push_str(name);
+                            if let Some(value) = value {
+                                if self.options.spaces_around_equals {
+                                    member.push_str(&format!(" = {}", value));
+                                } else {
+                                    member.push_str(&format!("={}", value));
+                                }
+                            }
+                            member
+                        })
+                        .collect::<Vec<String>>()
+                        .join(",\n"),
                 );
-                output.push('}');
+                output.push('\n');
+                output.push_str(&self.indent(indent));
+                output.push_str("};\n");
+            }
+            Statement::ClassDef(ident) => {
+                output.push_str(&self.indent(indent));
+                output.push_str(&format!("class {};\n", self.render_node(*ident, indent)?));
+            }
+            Statement::ClassDelete(ident) => {
+                output.push_str(&self.indent(indent));
+                output.push_str(&format!("delete {};\n", self.render_node(*ident, indent)?));
+            }
+            Statement::Config(nodes) => output.push_str(&self.render_nodes(nodes, indent)?),
+            Statement::Array(nodes) => output.push_str(&self.render_array(nodes, indent, 0)?),
+            Statement::Annotated { leading, node, trailing } => {
+                if self.options.comments {
+                    output.push_str(&self.render_trivia(leading, indent));
+                }
+                let rendered = self.render_item(*node, indent)?;
+                match trailing {
+                    Some(comment) if self.options.comments => {
+                        let content = rendered.trim_end_matches('\n');
+                        output.push_str(content);
+                        output.push(' ');
+                        output.push_str(&comment);
+                        output.push('\n');
+                    }
+                    _ => output.push_str(&rendered),
+                }
+            }
+            Statement::Trivia(trivia) => {
+                if self.options.comments {
+                    output.push_str(&self.render_trivia(trivia, indent));
+                }
             }
             Statement::Processed(stmt, _) => output.push_str(&self.render_statement(*stmt, indent)?),
             Statement::Defined(node, _) => output.push_str(&self.render_node(*node.clone(), indent)?),
             Statement::Inserted(nodes) => output.push_str(&self.render_nodes(nodes, indent)?),
             // Should be processed out
+            Statement::Unquoted(nodes) | Statement::MacroCallArg(nodes) if self.source => {
+                for node in nodes {
+                    output.push_str(&self.render_statement(node.statement, indent)?);
+                }
+            }
+            Statement::MacroCall { ident, args } if self.source => {
+                output.push_str(&ident);
+                output.push('(');
+                output.push_str(
+                    &args
+                        .into_iter()
+                        .map(|x| self.render_statement(x.statement, indent))
+                        .collect::<Result<Vec<String>, ArmaLintError>>()?
+                        .join(","),
+                );
+                output.push(')');
+            }
+            Statement::FILE if self.source => output.push_str("__FILE__"),
+            Statement::LINE if self.source => output.push_str("__LINE__"),
+            Statement::IfDef {
+                ident,
+                positive,
+                negative,
+            } if self.source => {
+                output.push_str(&self.render_conditional(format!("#ifdef {}", ident), positive, negative, indent, true)?)
+            }
+            Statement::IfNDef {
+                ident,
+                positive,
+                negative,
+            } if self.source => {
+                output.push_str(&self.render_conditional(format!("#ifndef {}", ident), positive, negative, indent, true)?)
+            }
+            Statement::If {
+                condition,
+                positive,
+                negative,
+            } if self.source => {
+                output.push_str(&self.render_conditional(format!("#if {}", condition), positive, negative, indent, true)?)
+            }
+            Statement::Include { content, .. } => output.push_str(&self.render_node(*content, indent)?),
             Statement::Unquoted(nodes) => output.push_str(&self.render_nodes(nodes, indent)?),
             Statement::FILE => panic!("A file marker was not processed out, this should be reported as a bug"),
             Statement::LINE => panic!("A line marker was not processed out, this should be reported as a bug"),
             Statement::IfDef { .. } => panic!("An IfDef marker was not processed out, this should be reported as a bug"),
+            Statement::IfNDef { .. } => panic!("An IfNDef marker was not processed out, this should be reported as a bug"),
+            Statement::If { .. } => panic!("An If marker was not processed out, this should be reported as a bug"),
             Statement::MacroBody(_) => panic!("A MacroBody marker was not processed out, this should be reported as a bug"),
             Statement::MacroCallArg(_) => {
                 panic!("A MacroCallArg marker was not processed out, this should be reported as a bug")
@@ -114,13 +334,72 @@ impl Renderer {
             Statement::MacroCall { .. } => {}
             Statement::Undefine(_) => {}
             Statement::Undefined(_, _) => {}
-            // Warnings & Errors
-            Statement::NonUppercaseDefine(_) => {}
-            Statement::Redefine(_, _, _) => {}
         }
         Ok(output)
     }
 
+    /// Renders an array on one line, or one element per line if it would not fit in `max_width`
+    ///
+    /// `width` is the length of the line before the array, including the character that follows it.
+    fn render_array(self, nodes: Vec<Node>, indent: u8, width: usize) -> Result<String, ArmaLintError> {
+        let inline = nodes
+            .iter()
+            .map(|x| self.render_statement(x.statement.clone(), indent))
+            .collect::<Result<Vec<String>, ArmaLintError>>()?;
+        let length = inline.iter().map(|x| x.len() + 2).sum::<usize>();
+        if nodes.is_empty() || width + length <= self.options.max_width || inline.iter().any(|x| x.contains('\n')) {
+            return Ok(format!("{{{}}}", inline.join(", ")));
+        }
+        let width = self.indent_width(indent + 1);
+        let mut elements = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            elements.push(match array_elements(node.statement) {
+                Ok(nested) => self.render_array(nested, indent + 1, width + 1)?,
+                Err(statement) => self.render_statement(statement, indent + 1)?,
+            });
+        }
+        let mut output = String::from("{\n");
+        output.push_str(
+            &elements
+                .into_iter()
+                .map(|x| format!("{}{}", self.indent(indent + 1), x))
+                .collect::<Vec<String>>()
+                .join(",\n"),
+        );
+        if self.options.trailing_comma {
+            output.push(',');
+        }
+        output.push('\n');
+        output.push_str(&self.indent(indent));
+        output.push('}');
+        Ok(output)
+    }
+
+    fn render_trivia(self, trivia: Vec<Trivia>, indent: u8) -> String {
+        let mut output = String::new();
+        for item in trivia {
+            match item {
+                Trivia::Comment(comment) => {
+                    output.push_str(&self.indent(indent));
+                    output.push_str(&comment);
+                    output.push('\n');
+                }
+                Trivia::BlankLine => output.push('\n'),
+            }
+        }
+        output
+    }
+
+    /// Columns used by the indentation, tabs count as 4
+    fn indent_width(self, indent: u8) -> usize {
+        indent as usize
+            * match self.options.indentation_type {
+                IndentationType::Tab => 4,
+                IndentationType::Spaces(u) => u as usize,
+                IndentationType::None => 0,
+            }
+    }
+
     fn indent(self, indent: u8) -> String {
         repeat!(
             match self.options.indentation_type {
@@ -135,8 +414,102 @@ impl Renderer {
 
 impl Default for Renderer {
     fn default() -> Self {
-        Self {
-            options: RenderOptions::default(),
-        }
+        Self::new(RenderOptions::default())
     }
 }
+
+/// Whether a statement is a class with a body
+fn is_class(statement: &Statement) -> bool {
+    match statement {
+        Statement::Class { .. } => true,
+        Statement::Annotated { node, .. } => is_class(&node.statement),
+        Statement::Processed(statement, _) => is_class(statement),
+        _ => false,
+    }
+}
+
+/// The elements of an array value, or the statement if it is not an array
+fn array_elements(statement: Statement) -> Result<Vec<Node>, Statement> {
+    match statement {
+        Statement::Array(nodes) => Ok(nodes),
+        Statement::Processed(inner, original) => match array_elements(*inner) {
+            Ok(nodes) => Ok(nodes),
+            Err(inner) => Err(Statement::Processed(Box::new(inner), original)),
+        },
+        statement => Err(statement),
+    }
+}
+
+/// Formats a float so it reads back as the same float, whole numbers keep a `.0`
+fn float(value: f32) -> String {
+    let mut output = value.to_string();
+    if value.is_finite() && !output.contains('.') {
+        output.push_str(".0");
+    }
+    output
+}
+
+fn node(statement: Statement) -> Node {
+    Node {
+        file: String::new(),
+        start: (0, (0, 0)),
+        end: (0, (0, 0)),
+        line: String::new(),
+        statement,
+    }
+}
+
+fn ident(name: &str) -> Box<Node> {
+    Box::new(node(Statement::Ident(name.to_string())))
+}
+
+/// Converts the entries of a simplified class back into nodes that can be rendered
+fn class_nodes(class: &Class) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    for (name, entry) in &class.entries {
+        let statement = match entry {
+            Entry::Class(c) if c.deletion => Statement::ClassDelete(ident(name)),
+            Entry::Class(c) if c.external => Statement::ClassDef(ident(name)),
+            Entry::Class(c) => Statement::Class {
+                ident: ident(name),
+                extends: if c.parent.is_empty() { None } else { Some(ident(&c.parent)) },
+                props: class_nodes(c),
+            },
+            Entry::Array(a) => Statement::Property {
+                ident: Box::new(node(Statement::IdentArray(name.clone()))),
+                value: Box::new(node(Statement::Array(a.elements.iter().map(element_node).collect()))),
+                expand: a.expand,
+            },
+            Entry::Invisible(entries) => {
+                nodes.append(&mut class_nodes(&Class {
+                    parent: String::new(),
+                    external: false,
+                    deletion: false,
+                    entries: entries.clone(),
+                }));
+                continue;
+            }
+            value => Statement::Property {
+                ident: ident(name),
+                value: Box::new(node(match value {
+                    Entry::Str(s) => Statement::Str(s.clone()),
+                    Entry::Float(f) => Statement::Float(*f),
+                    Entry::Int(i) => Statement::Integer(*i),
+                    _ => unreachable!(),
+                })),
+                expand: false,
+            },
+        };
+        nodes.push(node(statement));
+    }
+    nodes
+}
+
+fn element_node(element: &ArrayElement) -> Node {
+    node(match element {
+        ArrayElement::Str(s) => Statement::Str(s.clone()),
+        ArrayElement::Float(f) => Statement::Float(*f),
+        ArrayElement::Int(i) => Statement::Integer(*i),
+        ArrayElement::Array(a) => Statement::Array(a.elements.iter().map(element_node).collect()),
+    })
+}
@@ -1,3 +1,9 @@
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::ArmaLintError;
+
 #[derive(Clone, Copy, Hash, PartialEq)]
 pub enum BracketStyle {
     /// Linux Kernel
@@ -24,10 +30,23 @@ pub enum IndentationType {
     None,
 }
 
-#[derive(Clone, Copy, Hash, PartialEq)]
+#[derive(Clone, Copy, Hash, PartialEq, Deserialize)]
+#[serde(try_from = "RenderSection")]
 pub struct RenderOptions {
     pub bracket_style: BracketStyle,
     pub indentation_type: IndentationType,
+    /// Print the comments and blank lines of a CST
+    pub comments: bool,
+    /// Arrays that would make a line longer than this are wrapped with one element per line
+    pub max_width: usize,
+    /// Add a comma after the last element of a wrapped array
+    pub trailing_comma: bool,
+    /// Separate classes from the items around them with a blank line
+    pub blank_line_between_classes: bool,
+    /// `value = 1` instead of `value=1`
+    pub spaces_around_equals: bool,
+    /// `class A : B` instead of `class A: B`
+    pub space_before_colon: bool,
 }
 
 impl Default for RenderOptions {
@@ -35,10 +54,85 @@ impl Default for RenderOptions {
         Self {
             bracket_style: BracketStyle::Allman,
             indentation_type: IndentationType::Spaces(4),
+            comments: true,
+            max_width: 120,
+            trailing_comma: false,
+            blank_line_between_classes: false,
+            spaces_around_equals: true,
+            space_before_colon: false,
         }
     }
 }
 
+/// The `[render]` section of a project file
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RenderSection {
+    bracket_style: Option<String>,
+    indentation: Option<toml::Value>,
+    comments: Option<bool>,
+    max_width: Option<usize>,
+    trailing_comma: Option<bool>,
+    blank_line_between_classes: Option<bool>,
+    spaces_around_equals: Option<bool>,
+    space_before_colon: Option<bool>,
+}
+
+impl TryFrom<RenderSection> for RenderOptions {
+    type Error = String;
+
+    fn try_from(section: RenderSection) -> Result<Self, String> {
+        let invalid = |key: &str, value: &dyn std::fmt::Display| format!("invalid `{}` value `{}`", key, value);
+        let default = Self::default();
+        Ok(Self {
+            bracket_style: match section.bracket_style.as_deref() {
+                None => default.bracket_style,
+                Some("linux") => BracketStyle::Linux,
+                Some("allman") => BracketStyle::Allman,
+                Some(other) => return Err(invalid("bracket_style", &other)),
+            },
+            indentation_type: match section.indentation {
+                None => default.indentation_type,
+                Some(toml::Value::Integer(i)) if i >= 0 && i <= i64::from(u8::MAX) => IndentationType::Spaces(i as u8),
+                Some(toml::Value::String(ref s)) if s == "tab" => IndentationType::Tab,
+                Some(toml::Value::String(ref s)) if s == "none" => IndentationType::None,
+                Some(other) => return Err(invalid("indentation", &other)),
+            },
+            comments: section.comments.unwrap_or(default.comments),
+            max_width: section.max_width.unwrap_or(default.max_width),
+            trailing_comma: section.trailing_comma.unwrap_or(default.trailing_comma),
+            blank_line_between_classes: section
+                .blank_line_between_classes
+                .unwrap_or(default.blank_line_between_classes),
+            spaces_around_equals: section.spaces_around_equals.unwrap_or(default.spaces_around_equals),
+            space_before_colon: section.space_before_colon.unwrap_or(default.space_before_colon),
+        })
+    }
+}
+
+impl RenderOptions {
+    /// Reads the options from the `[render]` section of a project file
+    ///
+    /// ```toml
+    /// [render]
+    /// bracket_style = "linux"
+    /// indentation = 4 # or "tab", "none"
+    /// max_width = 100
+    /// trailing_comma = true
+    /// ```
+    /// Options that are not set keep their default.
+    pub fn from_toml(content: &str) -> Result<Self, ArmaLintError> {
+        #[derive(Deserialize)]
+        struct ProjectFile {
+            #[serde(default)]
+            render: RenderOptions,
+        }
+        toml::from_str::<ProjectFile>(content)
+            .map(|file| file.render)
+            .map_err(|e| ArmaLintError::InvalidInput(e.to_string()))
+    }
+}
+
 #[test]
 fn test_brackets() {
     let content = std::fs::read_to_string("tests/basic.cpp").unwrap();
@@ -50,6 +144,8 @@ fn test_brackets() {
         let linux_options = RenderOptions {
             bracket_style: BracketStyle::Linux,
             indentation_type: IndentationType::Spaces(4),
+            comments: true,
+            ..RenderOptions::default()
         };
         let renderer = super::Renderer::new(linux_options);
         assert_eq!(
@@ -69,6 +165,8 @@ class TestClass: Test {
         let linux_options = RenderOptions {
             bracket_style: BracketStyle::Allman,
             indentation_type: IndentationType::Spaces(4),
+            comments: true,
+            ..RenderOptions::default()
         };
         let renderer = super::Renderer::new(linux_options);
         assert_eq!(
@@ -98,6 +196,8 @@ fn test_indents() {
         let linux_options = RenderOptions {
             bracket_style: BracketStyle::Linux,
             indentation_type: IndentationType::Tab,
+            comments: true,
+            ..RenderOptions::default()
         };
         let renderer = super::Renderer::new(linux_options);
         assert_eq!(
@@ -118,6 +218,8 @@ class TestClass: Test {
         let linux_options = RenderOptions {
             bracket_style: BracketStyle::Linux,
             indentation_type: IndentationType::Spaces(4),
+            comments: true,
+            ..RenderOptions::default()
         };
         let renderer = super::Renderer::new(linux_options);
         assert_eq!(
@@ -134,3 +236,68 @@ class TestClass: Test {
         );
     }
 }
+
+#[test]
+fn test_project_file() {
+    let options = RenderOptions::from_toml(
+        r##"
+[render]
+bracket_style = "linux"
+indentation = "tab"
+max_width = 80
+space_before_colon = true
+"##,
+    )
+    .unwrap();
+    assert!(options.bracket_style == BracketStyle::Linux);
+    assert!(options.indentation_type == IndentationType::Tab);
+    assert_eq!(options.max_width, 80);
+    assert!(options.space_before_colon);
+    assert!(options.spaces_around_equals);
+    assert!(RenderOptions::from_toml("").unwrap() == RenderOptions::default());
+    assert!(RenderOptions::from_toml("[render]\nbracket_style = \"k&r\"").is_err());
+    assert!(RenderOptions::from_toml("[render]\nwidth = 80").is_err());
+}
+
+#[test]
+fn test_wrapping() {
+    let content = r##"class Base;
+class Test: Base {
+    magazines[] = {"30Rnd_65x39_caseless_mag", "30Rnd_65x39_caseless_mag_Tracer", {1, 2}};
+    short[] = {1, 2, 3};
+    class Inner { value = 1; };
+    after = 2;
+};
+"##;
+    let ast = crate::config::parse("wrapping.cpp", content).unwrap();
+    let mut preprocessor = crate::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    let renderer = super::Renderer::new(RenderOptions {
+        bracket_style: BracketStyle::Linux,
+        max_width: 60,
+        trailing_comma: true,
+        blank_line_between_classes: true,
+        spaces_around_equals: false,
+        space_before_colon: true,
+        ..RenderOptions::default()
+    });
+    assert_eq!(
+        renderer.render(processed).unwrap(),
+        r##"class Base;
+
+class Test : Base {
+    magazines[]={
+        "30Rnd_65x39_caseless_mag",
+        "30Rnd_65x39_caseless_mag_Tracer",
+        {1, 2},
+    };
+    short[]={1, 2, 3};
+
+    class Inner {
+        value=1;
+    };
+
+    after=2;
+};"##
+    );
+}
@@ -1,15 +1,23 @@
-use super::Node;
+use super::Diagnostic;
 
 #[derive(Clone, Debug)]
 pub struct Report {
-    pub errors: Vec<Node>,
-    pub warnings: Vec<Node>,
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
 }
 
 impl Report {
     pub fn new() -> Self {
         Report::default()
     }
+
+    /// Adds a diagnostic to the errors or warnings by its severity
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        match diagnostic.severity {
+            super::Severity::Error => self.errors.push(diagnostic),
+            super::Severity::Warning => self.warnings.push(diagnostic),
+        }
+    }
 }
 
 impl Default for Report {
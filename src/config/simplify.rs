@@ -3,12 +3,13 @@ use crate::ArmaLintError;
 
 use strum::AsStaticRef;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Config {
     pub root: Class,
+    pub enums: Vec<(String, i32)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Class {
     pub parent: String,
     pub external: bool,
@@ -16,7 +17,7 @@ pub struct Class {
     pub entries: Vec<(String, Entry)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Entry {
     Str(String),
     Float(f32),
@@ -38,13 +39,13 @@ impl Into<ArrayElement> for Entry {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Array {
     pub expand: bool,
     pub elements: Vec<ArrayElement>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ArrayElement {
     Str(String),
     Float(f32),
@@ -59,6 +60,7 @@ impl Config {
         }
         if let Statement::Config(inner) = ast.config.statement {
             Ok(Config {
+                enums: get_enums(&inner),
                 root: Class {
                     parent: String::new(),
                     external: false,
@@ -72,6 +74,20 @@ impl Config {
     }
 }
 
+pub fn get_enums(nodes: &[Node]) -> Vec<(String, i32)> {
+    let mut enums = Vec::new();
+    for node in nodes {
+        match &node.statement {
+            Statement::Enum(members) => enums.append(&mut super::enum_values(members)),
+            Statement::Class { props, .. } => enums.append(&mut get_enums(props)),
+            Statement::Annotated { node, .. } => enums.append(&mut get_enums(std::slice::from_ref(&**node))),
+            Statement::Config(inner) | Statement::Inserted(inner) => enums.append(&mut get_enums(inner)),
+            _ => {}
+        }
+    }
+    enums
+}
+
 pub fn get_entries(nodes: Vec<Node>) -> Result<Vec<(String, Entry)>, ArmaLintError> {
     let mut entries = Vec::new();
     for node in nodes {
@@ -114,13 +130,31 @@ pub fn get_entry(node: Node) -> Result<Option<(String, Entry)>, ArmaLintError> {
                 entries: Vec::new(),
             }),
         )),
+        Statement::ClassDelete(ident) => Some((
+            super::get_ident(ident.statement)?,
+            Entry::Class(Class {
+                parent: String::new(),
+                deletion: true,
+                external: false,
+                entries: Vec::new(),
+            }),
+        )),
         Statement::Property { ident, value, expand } => {
             Some((super::get_ident(ident.statement)?, get_value(value.statement, expand)?))
         }
         Statement::Config(inner) => Some((String::new(), Entry::Invisible(get_entries(inner)?))),
+        Statement::Inserted(inner) => Some((String::new(), Entry::Invisible(get_entries(inner)?))),
+        Statement::Annotated { node, .. } => get_entry(*node)?,
+        Statement::Include { content, .. } => get_entry(*content)?,
         // Ignore
         Statement::DefineMacro { .. } => None,
         Statement::Define { .. } => None,
+        Statement::Undefine(_) => None,
+        Statement::Exec(_) => None,
+        Statement::Enum(_) => None,
+        Statement::Trivia(_) => None,
+        Statement::Undefined(_, _) => None,
+        Statement::Gone => None,
         _ => {
             panic!("Not ready for {:#?}", node);
         }
@@ -139,6 +173,7 @@ pub fn get_value(statement: Statement, expand: bool) -> Result<Entry, ArmaLintEr
             elements: get_array(val)?,
         }),
         Statement::Defined(val, _) => get_value(val.statement, expand)?,
+        Statement::Eval(code) => Entry::Str(format!("__EVAL({})", code)),
         _ => {
             return Err(ArmaLintError::InvalidProperty(format!(
                 "Invalid property type `{}`",
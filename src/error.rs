@@ -1,11 +1,12 @@
 pub trait PrintableError<T, E> {
+    /// Prints an error to stderr and exits with `3`, the code of `ArmaLintError::exit_code` for failures
     fn unwrap_or_print(self) -> T;
 }
 impl<T, E: std::fmt::Debug + std::fmt::Display> PrintableError<T, E> for Result<T, E> {
     fn unwrap_or_print(self) -> T {
         if let Err(error) = &self {
-            println!("{}", error);
-            std::process::exit(1);
+            eprintln!("{}", error);
+            std::process::exit(3);
         }
         self.unwrap()
     }
@@ -18,10 +19,26 @@ pub enum ArmaLintError {
         negatives: Vec<String>,
         position: pest::error::LineColLocation,
     },
+    /// A file with syntax errors, they are in the errors of its report
+    SyntaxErrors(Box<crate::config::AST>),
     InvalidInput(String),
     InvalidProperty(String),
     NotProcessed,
     NotRoot,
+    /// The chain of includes from the first file that is included again
+    IncludeCycle(Vec<String>),
+    /// An included file that the resolver does not have
+    IncludeNotFound(String),
+    /// Lint found errors, or more warnings than allowed
+    Lint {
+        errors: usize,
+        warnings: usize,
+        max_warnings: Option<usize>,
+    },
+    Rapified(RapifiedError),
+    Pbo(PboError),
+    /// Invalid command line arguments, the message has the usage of the command
+    Usage(String),
 
     // Wrappers
     IO(std::io::Error),
@@ -30,11 +47,32 @@ pub enum ArmaLintError {
 }
 
 impl ArmaLintError {
+    /// Exit code of the process, `1` when lint found errors, `2` when it found too many warnings
+    /// and `3` for every other failure
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ArmaLintError::Lint { errors, .. } if *errors > 0 => 1,
+            ArmaLintError::Lint { .. } => 2,
+            _ => 3,
+        }
+    }
+    /// Prints the error to stderr and exits with its `exit_code`
+    pub fn exit(&self) -> ! {
+        eprintln!("{}", self);
+        std::process::exit(self.exit_code());
+    }
     pub fn warn(&self) {
         warn!(self);
     }
+    /// Prints the error, syntax errors are shown with the source they are in
     pub fn error(&self) {
-        error!(self);
+        if let ArmaLintError::SyntaxErrors(ast) = self {
+            for error in &ast.report.as_ref().unwrap().errors {
+                diagnostic!(ast.files, error);
+            }
+        } else {
+            error!(self);
+        }
     }
 }
 
@@ -46,8 +84,44 @@ impl std::fmt::Display for ArmaLintError {
             ArmaLintError::PATH(ref err) => write!(f, "IO error {}: {}", err.path.display(), err.source),
             ArmaLintError::NotProcessed => write!(f, "Attempt to perform action on non-processed AST"),
             ArmaLintError::NotRoot => write!(f, "The root of the AST is required"),
+            ArmaLintError::SyntaxErrors(ref ast) => {
+                let errors = &ast.report.as_ref().unwrap().errors;
+                write!(
+                    f,
+                    "{} syntax error{} in `{}`",
+                    errors.len(),
+                    if errors.len() == 1 { "" } else { "s" },
+                    ast.config.file
+                )?;
+                for error in errors {
+                    write!(f, "\n  {}:{}: {}", error.span.start.0, error.span.start.1, error.message)?;
+                }
+                Ok(())
+            }
+            ArmaLintError::IncludeCycle(ref chain) => write!(f, "Include cycle: {}", chain.join(" -> ")),
+            ArmaLintError::IncludeNotFound(ref file) => {
+                write!(f, "Invalid Input: Unable to find included file `{}`", file)
+            }
+            ArmaLintError::Lint {
+                errors,
+                warnings,
+                max_warnings,
+            } => {
+                let plural = |count: usize, name: &str| format!("{} {}{}", count, name, if count == 1 { "" } else { "s" });
+                if errors > 0 {
+                    write!(f, "Found {} and {}", plural(errors, "error"), plural(warnings, "warning"))
+                } else {
+                    match max_warnings {
+                        Some(0) | None => write!(f, "Found {}, warnings are denied", plural(warnings, "warning")),
+                        Some(max) => write!(f, "Found {}, at most {} are allowed", plural(warnings, "warning"), max),
+                    }
+                }
+            }
             ArmaLintError::InvalidInput(ref err) => write!(f, "Invalid Input: {}", err),
             ArmaLintError::InvalidProperty(ref err) => write!(f, "Invalid Property: {}", err),
+            ArmaLintError::Rapified(ref err) => write!(f, "Invalid rapified config: {}", err),
+            ArmaLintError::Pbo(ref err) => write!(f, "Invalid PBO: {}", err),
+            ArmaLintError::Usage(ref err) => write!(f, "{}", err),
             ArmaLintError::ParsingError {
                 ref positives,
                 ref position,
@@ -63,8 +137,129 @@ impl From<std::io::Error> for ArmaLintError {
     }
 }
 
+impl From<RapifiedError> for ArmaLintError {
+    fn from(err: RapifiedError) -> ArmaLintError {
+        ArmaLintError::Rapified(err)
+    }
+}
+
+impl From<PboError> for ArmaLintError {
+    fn from(err: PboError) -> ArmaLintError {
+        ArmaLintError::Pbo(err)
+    }
+}
+
 #[derive(Debug)]
 pub struct IOPathError {
     pub source: std::io::Error,
     pub path: std::path::PathBuf,
 }
+
+/// Problems found while reading a rapified config, offsets are from the start of the file
+#[derive(Debug, Clone, PartialEq)]
+pub enum RapifiedError {
+    /// The file does not start with `\0raP`
+    InvalidMagic,
+    UnexpectedEnd {
+        offset: u64,
+    },
+    /// A compressed integer longer than 5 bytes or larger than `u32`
+    InvalidCompressedInt {
+        offset: u64,
+    },
+    /// A count that is larger than the rest of the file could hold
+    InvalidCount {
+        offset: u64,
+        count: u32,
+    },
+    /// An offset pointing outside of the file or into the header
+    InvalidOffset {
+        offset: u64,
+        target: u32,
+    },
+    /// A class body that is one of its own parents
+    CyclicClassBody {
+        offset: u64,
+        target: u32,
+    },
+    /// A class body that was already read for another class
+    SharedClassBody {
+        offset: u64,
+        target: u32,
+    },
+    InvalidEntryType {
+        offset: u64,
+        entry_type: u8,
+    },
+    InvalidSubtype {
+        offset: u64,
+        subtype: u8,
+    },
+    InvalidArrayElement {
+        offset: u64,
+        element_type: u8,
+    },
+    /// Classes or arrays nested deeper than the reader allows
+    TooDeep {
+        offset: u64,
+    },
+}
+
+impl std::fmt::Display for RapifiedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            RapifiedError::InvalidMagic => write!(f, "missing `raP` signature"),
+            RapifiedError::UnexpectedEnd { offset } => write!(f, "unexpected end of file at {}", offset),
+            RapifiedError::InvalidCompressedInt { offset } => write!(f, "invalid compressed integer at {}", offset),
+            RapifiedError::InvalidCount { offset, count } => {
+                write!(f, "count {} at {} is larger than the rest of the file", count, offset)
+            }
+            RapifiedError::InvalidOffset { offset, target } => {
+                write!(f, "offset {} at {} is outside of the file", target, offset)
+            }
+            RapifiedError::CyclicClassBody { offset, target } => {
+                write!(f, "class body {} at {} contains itself", target, offset)
+            }
+            RapifiedError::SharedClassBody { offset, target } => {
+                write!(f, "class body {} at {} was already read", target, offset)
+            }
+            RapifiedError::InvalidEntryType { offset, entry_type } => {
+                write!(f, "unrecognized class entry type {} at {}", entry_type, offset)
+            }
+            RapifiedError::InvalidSubtype { offset, subtype } => {
+                write!(f, "unrecognized variable entry subtype {} at {}", subtype, offset)
+            }
+            RapifiedError::InvalidArrayElement { offset, element_type } => {
+                write!(f, "unrecognized array element type {} at {}", element_type, offset)
+            }
+            RapifiedError::TooDeep { offset } => write!(f, "nesting is too deep at {}", offset),
+        }
+    }
+}
+
+/// Problems found while reading or unpacking a PBO, offsets are from the start of the file
+#[derive(Debug, Clone, PartialEq)]
+pub enum PboError {
+    UnexpectedEnd {
+        offset: u64,
+    },
+    /// A compressed file that does not decompress to its original size or checksum
+    InvalidCompression {
+        file: String,
+    },
+    /// The SHA1 checksum at the end does not match the content
+    ChecksumMismatch,
+    /// A file name that points outside of the directory it is unpacked to
+    InvalidPath(String),
+}
+
+impl std::fmt::Display for PboError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PboError::UnexpectedEnd { offset } => write!(f, "unexpected end of file at {}", offset),
+            PboError::InvalidCompression { file } => write!(f, "unable to decompress `{}`", file),
+            PboError::ChecksumMismatch => write!(f, "the checksum does not match the content"),
+            PboError::InvalidPath(path) => write!(f, "file name `{}` is outside of the archive", path),
+        }
+    }
+}
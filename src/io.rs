@@ -12,13 +12,13 @@ impl<T: Read> ReadExt for T {
         for byte in self.bytes() {
             let b = byte?;
             if b == 0 {
-                break;
+                return Ok(decode_lossless(&bytes));
             } else {
                 bytes.push(b);
             }
         }
 
-        Ok(String::from_utf8(bytes).unwrap())
+        Err(io::Error::new(io::ErrorKind::UnexpectedEof, "unterminated string"))
     }
 
     fn read_compressed_int(&mut self) -> io::Result<u32> {
@@ -26,19 +26,27 @@ impl<T: Read> ReadExt for T {
 
         for (i, byte) in self.bytes().enumerate() {
             let b: u32 = byte?.into();
+            if i == 4 && b > 0x0f {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "compressed integer is too large"));
+            }
             result |= (b & 0x7f) << (i * 7);
 
             if b < 0x80 {
-                break;
+                return Ok(result);
             }
         }
 
-        Ok(result)
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "unterminated compressed integer",
+        ))
     }
 }
 
 pub trait WriteExt: Write {
     fn write_cstring<S: AsRef<[u8]>>(&mut self, s: S) -> io::Result<()>;
+    /// Writes a string read with `read_cstring`, giving back the bytes it was decoded from
+    fn write_cstring_lossless(&mut self, s: &str) -> io::Result<()>;
     fn write_compressed_int(&mut self, x: u32) -> io::Result<usize>;
 }
 
@@ -49,6 +57,10 @@ impl<T: Write> WriteExt for T {
         Ok(())
     }
 
+    fn write_cstring_lossless(&mut self, s: &str) -> io::Result<()> {
+        self.write_cstring(encode_lossless(s))
+    }
+
     fn write_compressed_int(&mut self, x: u32) -> io::Result<usize> {
         let mut temp = x;
         let mut len = 0;
@@ -77,3 +89,88 @@ pub fn compressed_int_len(x: u32) -> usize {
 
     len + 1
 }
+
+/// Start of the private use characters that stand in for bytes that are not valid UTF-8
+///
+/// Byte `0x80` to `0xFF` is decoded as `U+EF80` to `U+EFFF` and encoded back to the same byte.
+const ESCAPE: u32 = 0xEF00;
+
+fn is_escape(c: char) -> bool {
+    (ESCAPE + 0x80..=ESCAPE + 0xFF).contains(&(c as u32))
+}
+
+/// Decodes bytes as UTF-8, keeping invalid bytes so `encode_lossless` gives back the same bytes
+pub fn decode_lossless(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len());
+    let mut rest = bytes;
+    loop {
+        let (valid, invalid) = match std::str::from_utf8(rest) {
+            Ok(valid) => (valid, &[][..]),
+            Err(e) => {
+                let valid = std::str::from_utf8(&rest[..e.valid_up_to()]).unwrap();
+                let end = e.valid_up_to() + e.error_len().unwrap_or(rest.len() - e.valid_up_to());
+                (valid, &rest[e.valid_up_to()..end])
+            }
+        };
+        for c in valid.chars() {
+            if is_escape(c) {
+                // Escape the bytes of a character that would otherwise be encoded as a single byte
+                let mut buffer = [0; 4];
+                for b in c.encode_utf8(&mut buffer).bytes() {
+                    output.push(std::char::from_u32(ESCAPE + u32::from(b)).unwrap());
+                }
+            } else {
+                output.push(c);
+            }
+        }
+        for b in invalid {
+            output.push(std::char::from_u32(ESCAPE + u32::from(*b)).unwrap());
+        }
+        let consumed = valid.len() + invalid.len();
+        if consumed == rest.len() {
+            return output;
+        }
+        rest = &rest[consumed..];
+    }
+}
+
+/// Encodes a string as UTF-8, turning the characters of `decode_lossless` back into their bytes
+pub fn encode_lossless(s: &str) -> Vec<u8> {
+    let mut output = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        if is_escape(c) {
+            output.push((c as u32 - ESCAPE) as u8);
+        } else {
+            let mut buffer = [0; 4];
+            output.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+        }
+    }
+    output
+}
+
+/// Length of a string once written with `write_cstring_lossless`, without the terminator
+pub fn cstring_len(s: &str) -> usize {
+    s.chars().map(|c| if is_escape(c) { 1 } else { c.len_utf8() }).sum()
+}
+
+#[test]
+fn lossless() {
+    for bytes in &[
+        &b"plain"[..],
+        &[0x80, 0xff, b'a'][..],
+        "caf\u{e9}".as_bytes(),
+        &[0x63, 0xe9, 0x00][..2],
+        "\u{ef80}\u{efff}".as_bytes(),
+        &[0xe2, 0x82][..],
+    ] {
+        let decoded = decode_lossless(bytes);
+        assert_eq!(&encode_lossless(&decoded)[..], *bytes);
+        assert_eq!(cstring_len(&decoded), bytes.len());
+    }
+    assert_eq!(decode_lossless("caf\u{e9}".as_bytes()), "caf\u{e9}");
+
+    let mut output = Vec::new();
+    output.write_cstring([0x80, b'a']).unwrap();
+    output.write_cstring_lossless(&decode_lossless(&[0x80, b'a'])).unwrap();
+    assert_eq!(output, [0x80, b'a', 0, 0x80, b'a', 0]);
+}
@@ -19,11 +19,7 @@ pub mod io;
 
 //pub mod sqf;
 pub mod config;
-
-pub enum HelpType {
-    Help,
-    Note,
-}
+pub mod pbo;
 
 // Application
 pub mod commands;
@@ -56,6 +52,12 @@ pub fn execute(input: &[String]) -> Result<(), ArmaLintError> {
 
     // Add commands here
     commands.push(Box::new(crate::commands::Lint {}));
+    commands.push(Box::new(crate::commands::Fmt {}));
+    commands.push(Box::new(crate::commands::Derapify {}));
+    commands.push(Box::new(crate::commands::Rapify {}));
+    commands.push(Box::new(crate::commands::Pack {}));
+    commands.push(Box::new(crate::commands::Unpack {}));
+    commands.push(Box::new(crate::commands::List {}));
 
     for command in commands.iter() {
         let sub = command.register();
@@ -63,7 +65,13 @@ pub fn execute(input: &[String]) -> Result<(), ArmaLintError> {
         app = app.subcommand(sub);
     }
 
-    let matches = app.get_matches_from(input);
+    let matches = match app.get_matches_from_safe(input) {
+        Ok(matches) => matches,
+        Err(err) if err.kind == clap::ErrorKind::HelpDisplayed || err.kind == clap::ErrorKind::VersionDisplayed => {
+            err.exit()
+        }
+        Err(err) => return Err(ArmaLintError::Usage(err.message)),
+    };
 
     let start = if matches.is_present("time") {
         Some(Instant::now())
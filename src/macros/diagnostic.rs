@@ -0,0 +1,72 @@
+/// Prints the lines of a span, `underline` gives the text under the first line from the length of the range
+macro_rules! snippet {
+    ($files:expr, $span:expr, $underline:expr) => {{
+        use colored::Colorize;
+        let span: &crate::config::Span = &$span;
+        let sep = "|".blue().bold();
+        println!("  {} {} {}:{}", "-->".blue().bold(), span.file, span.start.0, span.start.1);
+        println!("    {}", sep);
+        if let Some((_, content)) = $files.get(&span.file) {
+            let lines = content
+                .lines()
+                .enumerate()
+                .skip(span.start.0.saturating_sub(1))
+                .take((span.end.0 + 1).saturating_sub(span.start.0));
+            for (number, line) in lines {
+                let number = number + 1;
+                println!(
+                    " {}{}{} {}",
+                    number.to_string().blue().bold(),
+                    repeat!(" ", 3usize.saturating_sub(number.to_string().len())),
+                    sep,
+                    line
+                );
+                if number == span.start.0 {
+                    let len = if span.end.0 == span.start.0 {
+                        span.end.1.saturating_sub(span.start.1)
+                    } else {
+                        (line.chars().count() + 1).saturating_sub(span.start.1)
+                    };
+                    println!(
+                        "    {} {}{}",
+                        sep,
+                        repeat!(" ", span.start.1.saturating_sub(1)),
+                        $underline(std::cmp::max(len, 1))
+                    );
+                }
+            }
+        }
+    }};
+}
+
+/// Prints a diagnostic with the source it points at
+macro_rules! diagnostic {
+    ($files:expr, $d:expr) => {{
+        use colored::Colorize;
+        let diagnostic: &crate::config::Diagnostic = &$d;
+        let color = match diagnostic.severity {
+            crate::config::Severity::Error => colored::Color::Red,
+            crate::config::Severity::Warning => colored::Color::Yellow,
+        };
+        println!(
+            "\n{}: {}",
+            format!("{}[{}]", diagnostic.severity, diagnostic.code).color(color).bold(),
+            diagnostic.message.bold()
+        );
+        snippet!($files, diagnostic.span, |len: usize| {
+            let arrows = repeat!("^", len).color(color).bold();
+            match &diagnostic.help {
+                Some(help) => format!("{} {}", arrows, format!("help: {}", help).yellow().bold()),
+                None => arrows.to_string(),
+            }
+        });
+        if let Some(note) = &diagnostic.note {
+            println!("    {} {}", "=".blue().bold(), format!("note: {}", note).blue().bold());
+        }
+        for label in &diagnostic.labels {
+            snippet!($files, label.span, |len: usize| {
+                format!("{} {}", repeat!("-", len), label.message).blue().bold().to_string()
+            });
+        }
+    }};
+}
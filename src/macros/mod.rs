@@ -2,7 +2,7 @@
 mod fs;
 
 #[macro_use]
-mod node;
+mod diagnostic;
 
 macro_rules! repeat {
     ($s:expr, $n:expr) => {{
@@ -10,12 +10,6 @@ macro_rules! repeat {
     }};
 }
 
-macro_rules! iformat {
-    ($e:expr, $($p:ident),*) => {
-        format!($e, $($p = $p,)*);
-    };
-}
-
 macro_rules! warn {
     ($e:expr) => {
         use colored::Colorize;
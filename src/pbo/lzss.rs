@@ -0,0 +1,62 @@
+/// Decompresses the LZSS data of a compressed PBO file into `length` bytes
+///
+/// Returns `None` when the data ends early, refers to data that does not exist or the checksum
+/// after the data does not match.
+pub fn decompress(input: &[u8], length: usize) -> Option<Vec<u8>> {
+    // Each pair of bytes expands to at most 18 bytes
+    let mut output = Vec::with_capacity(length.min(input.len() * 9));
+    let mut position = 0;
+    while output.len() < length {
+        let flags = *input.get(position)?;
+        position += 1;
+        for bit in 0..8 {
+            if output.len() >= length {
+                break;
+            }
+            if flags & (1 << bit) != 0 {
+                output.push(*input.get(position)?);
+                position += 1;
+            } else {
+                let low = usize::from(*input.get(position)?);
+                let high = usize::from(*input.get(position + 1)?);
+                position += 2;
+                let distance = low | ((high & 0xf0) << 4);
+                if distance == 0 {
+                    return None;
+                }
+                for _ in 0..(high & 0x0f) + 3 {
+                    if output.len() >= length {
+                        break;
+                    }
+                    // Data before the start of the file is read as spaces
+                    let byte = if distance > output.len() {
+                        b' '
+                    } else {
+                        output[output.len() - distance]
+                    };
+                    output.push(byte);
+                }
+            }
+        }
+    }
+
+    // The data is followed by the sum of the decompressed bytes
+    let checksum = input.get(position..position + 4)?;
+    let sum = output.iter().fold(0u32, |sum, b| sum.wrapping_add(u32::from(*b)));
+    if checksum != sum.to_le_bytes() {
+        return None;
+    }
+    Some(output)
+}
+
+#[test]
+fn back_references() {
+    // `abc` followed by a reference 3 bytes back that repeats it twice
+    let input = [0b0000_0111, b'a', b'b', b'c', 3, 3, 0x72, 0x03, 0, 0];
+    assert_eq!(decompress(&input, 9).unwrap(), b"abcabcabc");
+    // A reference before the start of the data
+    let input = [0b0000_0000, 4, 1, 0x80, 0, 0, 0];
+    assert_eq!(decompress(&input, 4).unwrap(), b"    ");
+    assert!(decompress(&input[..3], 4).is_none());
+    assert!(decompress(&[0b0000_0111, b'a', b'b', b'c', 3, 3, 0, 0, 0, 0], 9).is_none());
+}
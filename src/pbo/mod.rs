@@ -0,0 +1,280 @@
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use sha1::{Digest, Sha1};
+
+use crate::config::{IncludeKind, IncludeResolver};
+use crate::error::PboError;
+use crate::io::*;
+use crate::ArmaLintError;
+
+mod lzss;
+
+/// Packing method of the first header, it is followed by the header extensions
+const VERSION: u32 = 0x5665_7273;
+/// Packing method of files compressed with LZSS
+const COMPRESSED: u32 = 0x4370_7273;
+
+/// A PBO archive with the content of every file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pbo {
+    /// Header extensions such as `prefix`, in the order they are written
+    pub extensions: Vec<(String, String)>,
+    pub files: Vec<PboFile>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PboFile {
+    /// Path in the archive, separated by `\`
+    pub name: String,
+    /// Seconds since the unix epoch
+    pub timestamp: u32,
+    /// Uncompressed content of the file
+    pub data: Vec<u8>,
+}
+
+struct Header {
+    name: String,
+    method: u32,
+    original_size: u32,
+    timestamp: u32,
+    data_size: u32,
+}
+
+/// Keeps a SHA1 hash and the position of everything that is read or written
+struct Hashed<T> {
+    inner: T,
+    hasher: Sha1,
+    position: u64,
+}
+
+impl<T> Hashed<T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            hasher: Sha1::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for Hashed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<W: Write> Write for Hashed<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.position += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: Read> Hashed<R> {
+    /// Runs a read, reporting where it failed
+    fn read_with<T, F: FnOnce(&mut Self) -> std::io::Result<T>>(&mut self, read: F) -> Result<T, ArmaLintError> {
+        let offset = self.position;
+        read(self).map_err(|err| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => PboError::UnexpectedEnd { offset }.into(),
+            _ => err.into(),
+        })
+    }
+
+    fn header(&mut self) -> Result<Header, ArmaLintError> {
+        let name = self.read_with(|i| i.read_cstring())?;
+        let method = self.read_with(|i| i.read_u32::<LittleEndian>())?;
+        let original_size = self.read_with(|i| i.read_u32::<LittleEndian>())?;
+        let _reserved = self.read_with(|i| i.read_u32::<LittleEndian>())?;
+        let timestamp = self.read_with(|i| i.read_u32::<LittleEndian>())?;
+        let data_size = self.read_with(|i| i.read_u32::<LittleEndian>())?;
+        Ok(Header {
+            name,
+            method,
+            original_size,
+            timestamp,
+            data_size,
+        })
+    }
+}
+
+impl Pbo {
+    /// File in the root of an addon with its prefix, it is not packed
+    pub const PREFIX_FILE: &'static str = "$PBOPREFIX$";
+
+    /// Header extensions from the content of a prefix file
+    ///
+    /// Lines are `key=value`, a line without `=` is the prefix.
+    pub fn parse_prefix_file(content: &str) -> Vec<(String, String)> {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| match line.find('=') {
+                Some(i) => (line[..i].trim().to_string(), line[i + 1..].trim().to_string()),
+                None => ("prefix".to_string(), line.to_string()),
+            })
+            .collect()
+    }
+
+    /// Finds a header extension, `prefix` holds the path the files are mounted at
+    pub fn extension(&self, key: &str) -> Option<&str> {
+        self.extensions
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Finds a file by its path in the archive, case-insensitive and with either separator
+    pub fn file(&self, name: &str) -> Option<&PboFile> {
+        let name = name.replace('/', "\\");
+        self.files.iter().find(|f| f.name.eq_ignore_ascii_case(&name))
+    }
+
+    /// Path of a file with the prefix of the PBO, separated by `/`
+    pub fn path(&self, file: &PboFile) -> String {
+        match self.extension("prefix") {
+            Some(prefix) => normalize(&format!("{}/{}", prefix, file.name)).join("/"),
+            None => normalize(&file.name).join("/"),
+        }
+    }
+
+    /// Reads a PBO, compressed files are decompressed
+    ///
+    /// The SHA1 checksum at the end is checked when the archive has one.
+    pub fn read<I: Read>(input: I) -> Result<Pbo, ArmaLintError> {
+        let mut input = Hashed::new(BufReader::new(input));
+        let mut pbo = Pbo::default();
+
+        let mut headers = Vec::new();
+        loop {
+            let header = input.header()?;
+            if !header.name.is_empty() {
+                headers.push(header);
+            } else if header.method == VERSION && headers.is_empty() && pbo.extensions.is_empty() {
+                loop {
+                    let key = input.read_with(|i| i.read_cstring())?;
+                    if key.is_empty() {
+                        break;
+                    }
+                    let value = input.read_with(|i| i.read_cstring())?;
+                    pbo.extensions.push((key, value));
+                }
+            } else {
+                break;
+            }
+        }
+
+        for header in headers {
+            let offset = input.position;
+            let mut data = Vec::new();
+            (&mut input).take(header.data_size.into()).read_to_end(&mut data)?;
+            if data.len() != header.data_size as usize {
+                return Err(PboError::UnexpectedEnd { offset }.into());
+            }
+            if header.method == COMPRESSED && header.original_size != header.data_size {
+                data = match lzss::decompress(&data, header.original_size as usize) {
+                    Some(data) => data,
+                    None => return Err(PboError::InvalidCompression { file: header.name }.into()),
+                };
+            }
+            pbo.files.push(PboFile {
+                name: header.name,
+                timestamp: header.timestamp,
+                data,
+            });
+        }
+
+        // Older archives end without a checksum
+        let checksum = input.hasher.clone().finalize();
+        let mut trailer = Vec::new();
+        input.inner.read_to_end(&mut trailer)?;
+        if !trailer.is_empty() && (trailer.len() != 21 || trailer[0] != 0 || trailer[1..] != checksum[..]) {
+            return Err(PboError::ChecksumMismatch.into());
+        }
+
+        Ok(pbo)
+    }
+
+    /// Writes the PBO with every file uncompressed, followed by its SHA1 checksum
+    pub fn write<O: Write>(&self, output: &mut O) -> Result<(), ArmaLintError> {
+        let mut output = Hashed::new(std::io::BufWriter::new(output));
+
+        output.write_cstring("")?;
+        output.write_u32::<LittleEndian>(VERSION)?;
+        output.write_all(&[0; 16])?;
+        for (key, value) in &self.extensions {
+            output.write_cstring_lossless(key)?;
+            output.write_cstring_lossless(value)?;
+        }
+        output.write_cstring("")?;
+
+        for file in &self.files {
+            output.write_cstring_lossless(&file.name)?;
+            output.write_u32::<LittleEndian>(0)?;
+            output.write_u32::<LittleEndian>(file.data.len() as u32)?;
+            output.write_u32::<LittleEndian>(0)?;
+            output.write_u32::<LittleEndian>(file.timestamp)?;
+            output.write_u32::<LittleEndian>(file.data.len() as u32)?;
+        }
+        output.write_cstring("")?;
+        output.write_all(&[0; 20])?;
+
+        for file in &self.files {
+            output.write_all(&file.data)?;
+        }
+
+        let checksum = output.hasher.clone().finalize();
+        output.inner.write_all(&[0])?;
+        output.inner.write_all(&checksum)?;
+        output.inner.flush()?;
+        Ok(())
+    }
+}
+
+impl IncludeResolver for Pbo {
+    /// Finds an included file inside the PBO
+    ///
+    /// Names starting with `\` such as `\x\test\addons\main\macros.hpp` include the prefix, quoted names
+    /// are relative to `wd`, the directory of the including file with the prefix. Names are case-insensitive.
+    fn resolve(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Result<(String, PathBuf), ArmaLintError> {
+        let target = if kind == IncludeKind::System || filename.starts_with('\\') || filename.starts_with('/') {
+            normalize(filename).join("/")
+        } else {
+            normalize(&format!("{}/{}", wd.to_string_lossy(), filename)).join("/")
+        };
+        for file in &self.files {
+            let path = self.path(file);
+            if path.eq_ignore_ascii_case(&target) {
+                let wd = path.rfind('/').map_or("", |i| &path[..i]);
+                return Ok((String::from_utf8_lossy(&file.data).into_owned(), PathBuf::from(wd)));
+            }
+        }
+        Err(ArmaLintError::IncludeNotFound(filename.to_string()))
+    }
+}
+
+/// Parts of a path separated by `\` or `/`, with `.` and `..` applied
+fn normalize(path: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    for part in path.split(['\\', '/']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts
+}
@@ -0,0 +1,30 @@
+#define VERSION 3
+#define DEBUG
+
+#ifndef RELEASE
+mode = "dev";
+#else
+mode = "release";
+#endif
+
+#if VERSION >= 2 && defined(DEBUG)
+features = 2;
+#elif VERSION == 1
+features = 1;
+#else
+features = 0;
+#endif
+
+#if MISSING
+missing = 1;
+#endif
+
+#if 0
+disabled = 1;
+#endif
+
+class Settings {
+    #ifdef DEBUG
+    debug = 1;
+    #endif
+};
@@ -0,0 +1,96 @@
+use armalint::config::Code;
+
+const FILE: &str = "tests/conditional.cpp";
+const FILENAME: &str = "conditional.cpp";
+
+#[test]
+fn preprocess() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    assert!(processed.valid());
+    let warnings: Vec<(&str, String)> = processed
+        .report
+        .clone()
+        .unwrap()
+        .warnings
+        .into_iter()
+        .map(|w| (w.code.as_str(), w.message))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                "CFG009",
+                "Undefined identifier `MISSING` in condition evaluates to 0".to_string()
+            ),
+            ("CFG010", "Unreachable branch, condition is always false".to_string()),
+        ]
+    );
+    assert_eq!(
+        armalint::config::Renderer::default().render(processed).unwrap(),
+        r##"mode = "dev";
+features = 2;
+class Settings
+{
+    debug = 1;
+};"##
+    );
+}
+
+#[test]
+fn invalid_condition() {
+    let content = "#define NAME \"test\"\n#if NAME\nvalue = 1;\n#endif\n";
+    let ast = armalint::config::parse(FILENAME, content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    assert!(!processed.valid());
+    let errors = processed.report.unwrap().errors;
+    assert_eq!(errors[0].code, Code::InvalidCondition);
+    assert_eq!(errors[0].span.start, (2, 5));
+}
+
+#[test]
+fn unreachable_chain() {
+    let content = "#if 1\nvalue = 1;\n#elif X\nvalue = 2;\n#else\nvalue = 3;\n#endif\n";
+    let ast = armalint::config::parse(FILENAME, content).unwrap();
+    let processed = armalint::config::PreProcessor::new().process(ast).unwrap();
+    let warnings: Vec<(&str, String, usize)> = processed
+        .report
+        .unwrap()
+        .warnings
+        .into_iter()
+        .map(|w| (w.code.as_str(), w.message, w.span.start.0))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                "CFG009",
+                "Undefined identifier `X` in condition evaluates to 0".to_string(),
+                3
+            ),
+            (
+                "CFG010",
+                "Unreachable `#elif` branch, an earlier condition is always true".to_string(),
+                3
+            ),
+            (
+                "CFG010",
+                "Unreachable `#else` branch, an earlier condition is always true".to_string(),
+                3
+            ),
+        ]
+    );
+}
+
+#[test]
+fn simplify() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    let simple = armalint::config::simplify::Config::from_ast(processed).unwrap();
+    assert_eq!(simple.root.entries.len(), 3);
+}
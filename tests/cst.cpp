@@ -0,0 +1,15 @@
+// Vehicles added by the mod
+/* Block comment */
+
+class CfgVehicles {
+    // The base car
+    class Car; // External
+
+    class MyCar: Car {
+        displayName = "My Car"; // Shown in the editor
+
+
+        maxSpeed = 120;
+        // Nothing else yet
+    };
+};
@@ -0,0 +1,49 @@
+use armalint::config::render::{BracketStyle, IndentationType, RenderOptions, Renderer};
+
+const FILE: &str = "tests/cst.cpp";
+const FILENAME: &str = "cst.cpp";
+
+#[test]
+fn render_comments() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let cst = armalint::config::parse_cst(FILENAME, &content).unwrap();
+    let renderer = Renderer::new(RenderOptions {
+        bracket_style: BracketStyle::Linux,
+        indentation_type: IndentationType::Spaces(4),
+        comments: true,
+        ..RenderOptions::default()
+    });
+    assert_eq!(renderer.render(cst).unwrap(), content.trim());
+}
+
+#[test]
+fn render_without_comments() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let cst = armalint::config::parse_cst(FILENAME, &content).unwrap();
+    let renderer = Renderer::new(RenderOptions {
+        bracket_style: BracketStyle::Linux,
+        indentation_type: IndentationType::Spaces(4),
+        comments: false,
+        ..RenderOptions::default()
+    });
+    assert_eq!(
+        renderer.render(cst).unwrap(),
+        r##"class CfgVehicles {
+    class Car;
+    class MyCar: Car {
+        displayName = "My Car";
+        maxSpeed = 120;
+    };
+};"##
+    );
+}
+
+#[test]
+fn preprocess() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let cst = armalint::config::parse_cst(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(cst).unwrap();
+    let simple = armalint::config::simplify::Config::from_ast(processed).unwrap();
+    assert_eq!(simple.root.entries.len(), 1);
+}
@@ -0,0 +1,26 @@
+enum {
+    destructNo,
+    destructEngine = 5
+};
+class CfgPatches {
+    class Test {
+        units[] = {};
+        requiredVersion = 1.0;
+    };
+};
+class Car;
+class CfgVehicles {
+    class Car;
+    class Test_Car: Car {
+        displayName = "Test Car";
+        maxSpeed = 120;
+        armor = -1;
+        fuelConsumption = 0.1;
+        precise = 3.14159274;
+        tiny = 0.0000001;
+        offset = -2.5;
+        sounds[] = {"engine", {1, 0.5, -3}, ""};
+        sounds[] += {"horn"};
+        class Empty {};
+    };
+};
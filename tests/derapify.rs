@@ -0,0 +1,86 @@
+use armalint::config::simplify::{Class, Config, Entry};
+use armalint::config::Renderer;
+
+const FILE: &str = "tests/derapify.cpp";
+const FILENAME: &str = "derapify.cpp";
+
+fn simplify(content: &str) -> Config {
+    let ast = armalint::config::parse(FILENAME, content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    Config::from_ast(processed).unwrap()
+}
+
+#[test]
+fn render() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let mut cursor = simplify(&content).to_cursor().unwrap();
+    cursor.set_position(0);
+    let config = Config::read_rapified(&mut cursor).unwrap();
+    assert_eq!(
+        Renderer::default().render_config(&config).unwrap(),
+        r##"enum
+{
+    destructNo = 0,
+    destructEngine = 5
+};
+class CfgPatches
+{
+    class Test
+    {
+        units[] = {};
+        requiredVersion = 1.0;
+    };
+};
+class Car;
+class CfgVehicles
+{
+    class Car;
+    class Test_Car: Car
+    {
+        displayName = "Test Car";
+        maxSpeed = 120;
+        armor = -1;
+        fuelConsumption = 0.1;
+        precise = 3.1415927;
+        tiny = 0.0000001;
+        offset = -2.5;
+        sounds[] = {"engine", {1, 0.5, -3}, ""};
+        sounds[] += {"horn"};
+        class Empty {};
+    };
+};"##
+    );
+}
+
+#[test]
+fn round_trip() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let original = simplify(&content).to_cursor().unwrap();
+    let mut cursor = original.clone();
+    cursor.set_position(0);
+    let config = Config::read_rapified(&mut cursor).unwrap();
+    let rendered = Renderer::default().render_config(&config).unwrap();
+    let again = simplify(&rendered).to_cursor().unwrap();
+    assert_eq!(original.get_ref(), again.get_ref());
+}
+
+#[test]
+fn floats() {
+    for value in &[0.1f32, 1.0, 3.402_823_5e38, 1.175_494_4e-38, 123_456.79, -0.000_123] {
+        let config = Config {
+            root: Class {
+                parent: String::new(),
+                external: false,
+                deletion: false,
+                entries: vec![("value".to_string(), Entry::Float(*value))],
+            },
+            enums: Vec::new(),
+        };
+        let rendered = simplify(&Renderer::default().render_config(&config).unwrap());
+        match rendered.root.entries[0].1 {
+            Entry::Float(f) => assert_eq!(f.to_bits(), value.to_bits()),
+            ref other => panic!("{:?}", other),
+        }
+    }
+}
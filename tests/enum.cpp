@@ -0,0 +1,9 @@
+enum {
+    destructNo,
+    destructBuilding = 5,
+    destructEngine
+};
+
+class Vehicle {
+    destrType = destructEngine;
+};
@@ -0,0 +1,86 @@
+use armalint::config::simplify::Entry;
+
+const FILE: &str = "tests/enum.cpp";
+const FILENAME: &str = "enum.cpp";
+
+#[test]
+fn preprocess() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    assert!(processed.valid());
+    assert_eq!(
+        armalint::config::Renderer::default().render(processed).unwrap(),
+        r##"enum
+{
+    destructNo,
+    destructBuilding = 5,
+    destructEngine
+};
+class Vehicle
+{
+    destrType = 6;
+};"##
+    );
+}
+
+#[test]
+fn simplify() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    let simple = armalint::config::simplify::Config::from_ast(processed).unwrap();
+    assert_eq!(
+        simple.enums,
+        vec![
+            ("destructNo".to_string(), 0),
+            ("destructBuilding".to_string(), 5),
+            ("destructEngine".to_string(), 6),
+        ]
+    );
+}
+
+#[test]
+fn rapify() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    let simple = armalint::config::simplify::Config::from_ast(processed).unwrap();
+    let mut rapified = std::io::Cursor::new(Vec::new());
+    simple.write_rapified(&mut rapified).unwrap();
+    let bytes = rapified.get_ref();
+    let mut enum_section = vec![3, 0, 0, 0];
+    enum_section.extend_from_slice(b"destructNo\0\0\0\0\0");
+    enum_section.extend_from_slice(b"destructBuilding\0\x05\0\0\0");
+    enum_section.extend_from_slice(b"destructEngine\0\x06\0\0\0");
+    assert!(bytes.ends_with(&enum_section));
+    assert_eq!(simple.to_cursor().unwrap().get_ref().len(), bytes.len());
+
+    rapified.set_position(0);
+    let read = armalint::config::simplify::Config::read_rapified(&mut rapified).unwrap();
+    assert_eq!(read.enums, simple.enums);
+    match &read.root.entries[0].1 {
+        Entry::Class(c) => match c.entries[0].1 {
+            Entry::Int(6) => {}
+            _ => panic!("expected destrType = 6"),
+        },
+        _ => panic!("expected class"),
+    }
+}
+
+#[test]
+fn out_of_range() {
+    match armalint::config::parse(FILENAME, "enum { A = 99999999999 };\nvalue = -99999999999;") {
+        Err(armalint::error::ArmaLintError::SyntaxErrors(ast)) => {
+            let errors = &ast.report.unwrap().errors;
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].message, "`99999999999` is too large for an integer");
+            assert_eq!(errors[0].span.start, (1, 12));
+        }
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    assert!(armalint::config::parse(FILENAME, "value = -99999999999;").is_err());
+}
@@ -0,0 +1,11 @@
+__EXEC(_ratio = 1 / 4)
+__EXEC(_name = "Car")
+
+class Values {
+    third = __EVAL(1/4);
+    total = __EVAL(2 * 3 + 1);
+    fromExec = __EVAL(_ratio * 2);
+    name = __EVAL(_name + "_F");
+    unknown = __EVAL(getNumber (configFile >> "x"));
+    list[] = {__EVAL(10 mod 4), 1};
+};
@@ -0,0 +1,77 @@
+use armalint::config::simplify::{ArrayElement, Entry};
+use armalint::config::Code;
+
+const FILE: &str = "tests/eval.cpp";
+const FILENAME: &str = "eval.cpp";
+
+#[test]
+fn preprocess() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    assert!(processed.valid());
+    let warnings = processed.report.clone().unwrap().warnings;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, Code::InvalidEval);
+    assert_eq!(
+        armalint::config::Renderer::default().render(processed).unwrap(),
+        r##"__EXEC(_ratio = 1 / 4)
+__EXEC(_name = "Car")
+class Values
+{
+    third = 0.25;
+    total = 7;
+    fromExec = 0.5;
+    name = "Car_F";
+    unknown = __EVAL(getNumber (configFile >> "x"));
+    list[] = {2, 1};
+};"##
+    );
+}
+
+#[test]
+fn simplify() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    let simple = armalint::config::simplify::Config::from_ast(processed).unwrap();
+    let class = match &simple.root.entries[0].1 {
+        Entry::Class(c) => c,
+        _ => panic!("expected class"),
+    };
+    let values: Vec<String> = class
+        .entries
+        .iter()
+        .map(|(name, entry)| match entry {
+            Entry::Str(v) => format!("{}={:?}", name, v),
+            Entry::Float(v) => format!("{}={}f", name, v),
+            Entry::Int(v) => format!("{}={}", name, v),
+            Entry::Array(a) => format!(
+                "{}={}",
+                name,
+                a.elements
+                    .iter()
+                    .map(|e| match e {
+                        ArrayElement::Int(v) => v.to_string(),
+                        _ => panic!("unexpected element"),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            _ => panic!("unexpected entry"),
+        })
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            "third=0.25f",
+            "total=7",
+            "fromExec=0.5f",
+            "name=\"Car_F\"",
+            "unknown=\"__EVAL(getNumber (configFile >> \\\"x\\\"))\"",
+            "list=2,1",
+        ]
+    );
+}
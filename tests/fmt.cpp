@@ -0,0 +1,21 @@
+#include "script_component.hpp"
+#define VERSION 3
+#define QUOTE(var) #var
+#define CAR(name,clr) class name: car {\
+    displayName = QUOTE(name - clr); \
+}
+
+// Cars
+class car;
+class CfgCars {
+      CAR(Avenger, red);
+#ifdef EXTRA
+  CAR(Titan, black);
+#elif VERSION > 2
+  version = VERSION;
+#else
+  old = 1;
+#endif
+  file = __FILE__;
+  values[] = {1,2,  3}; // Trailing
+};
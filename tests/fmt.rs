@@ -0,0 +1,69 @@
+use armalint::commands::Fmt;
+use armalint::config::render::{BracketStyle, IndentationType, RenderOptions};
+
+const FILE: &str = "tests/fmt.cpp";
+const FILENAME: &str = "fmt.cpp";
+
+#[test]
+fn format() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let formatted = Fmt::format(FILENAME, &content, RenderOptions::default()).unwrap();
+    assert_eq!(
+        formatted,
+        r##"#include "script_component.hpp"
+#define VERSION 3
+#define QUOTE(var) #var
+#define CAR(name,clr) class name: car {\
+    displayName = QUOTE(name - clr); \
+}
+
+// Cars
+class car;
+class CfgCars
+{
+    CAR(Avenger, red);
+    #ifdef EXTRA
+    CAR(Titan, black);
+    #elif VERSION > 2
+    version = VERSION;
+    #else
+    old = 1;
+    #endif
+    file = __FILE__;
+    values[] = {1, 2, 3}; // Trailing
+};
+"##
+    );
+}
+
+#[test]
+fn idempotent() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let options = RenderOptions {
+        bracket_style: BracketStyle::Linux,
+        indentation_type: IndentationType::Tab,
+        comments: true,
+        ..RenderOptions::default()
+    };
+    let formatted = Fmt::format(FILENAME, &content, options).unwrap();
+    assert_eq!(Fmt::format(FILENAME, &formatted, options).unwrap(), formatted);
+}
+
+#[test]
+fn empty() {
+    assert_eq!(Fmt::format(FILENAME, "", RenderOptions::default()).unwrap(), "");
+}
+
+#[test]
+fn wrapped_idempotent() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let options = RenderOptions {
+        max_width: 20,
+        trailing_comma: true,
+        blank_line_between_classes: true,
+        ..RenderOptions::default()
+    };
+    let formatted = Fmt::format(FILENAME, &content, options).unwrap();
+    assert!(formatted.contains("    values[] = {\n        1,\n        2,\n        3,\n    }; // Trailing\n"));
+    assert_eq!(Fmt::format(FILENAME, &formatted, options).unwrap(), formatted);
+}
@@ -0,0 +1 @@
+#define SPEED 120
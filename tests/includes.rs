@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use armalint::config::{Code, FsResolver, IncludeKind, IncludeResolver, PreProcessor, AST};
+use armalint::error::ArmaLintError;
+
+const DIR: &str = "tests/includes";
+
+fn parse(content: &str) -> Result<AST, ArmaLintError> {
+    let resolver = FsResolver::new();
+    armalint::config::parse_with_resolver("config.cpp", Path::new(DIR).to_path_buf(), content, &resolver)
+}
+
+fn cycle(content: &str) -> Vec<String> {
+    match parse(content) {
+        Err(ArmaLintError::IncludeCycle(chain)) => chain,
+        other => panic!("{:?}", other.map(|ast| ast.includes)),
+    }
+}
+
+#[test]
+fn cycles() {
+    assert_eq!(cycle("#include \"a.hpp\"\n"), vec!["a.hpp", "b.hpp", "a.hpp"]);
+    assert_eq!(cycle("#include \"self.hpp\"\n"), vec!["self.hpp", "self.hpp"]);
+    assert_eq!(cycle("#include \"config.cpp\"\n"), vec!["config.cpp", "config.cpp"]);
+    // A failed parse does not leave files on the include stack
+    assert!(parse("#include \"guard.hpp\"\n").is_ok());
+}
+
+#[test]
+fn graph() {
+    let ast = parse("#include \"guard.hpp\"\n#include \"plain.hpp\"\n").unwrap();
+    assert_eq!(
+        ast.includes,
+        vec![
+            ("config.cpp".to_string(), "guard.hpp".to_string()),
+            ("config.cpp".to_string(), "plain.hpp".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn nested_parse() {
+    // A resolver that parses the files it finds does not change the includes of the outer parse
+    let disk = FsResolver::new();
+    let parsing = |filename: &str, wd: &Path, kind: IncludeKind| {
+        let (content, dir) = disk.resolve(filename, wd, kind)?;
+        armalint::config::parse_with_resolver(filename, dir.clone(), &content, &disk)?;
+        Ok((content, dir))
+    };
+    let ast = armalint::config::parse_with_resolver(
+        "config.cpp",
+        Path::new(DIR).to_path_buf(),
+        "#include \"outer.hpp\"\n",
+        &parsing,
+    )
+    .unwrap();
+    assert_eq!(
+        ast.includes,
+        vec![
+            ("config.cpp".to_string(), "outer.hpp".to_string()),
+            ("outer.hpp".to_string(), "plain.hpp".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn duplicates() {
+    let content = "#include \"guard.hpp\"\n#include \"guard.hpp\"\nclass A {\n    #include \"plain.hpp\"\n};\nclass B {\n    #include \"plain.hpp\"\n};\n";
+    let processed = PreProcessor::new()
+        .warn_duplicate_includes()
+        .process(parse(content).unwrap())
+        .unwrap();
+    let warnings = processed.report.unwrap().warnings;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code, Code::DuplicateInclude);
+    assert_eq!(warnings[0].span.start.0, 7);
+
+    let processed = PreProcessor::new().process(parse(content).unwrap()).unwrap();
+    assert!(processed.report.unwrap().warnings.is_empty());
+
+    // Files are told apart by where they were found, not by how the include is written
+    let warnings = |content: &str| {
+        PreProcessor::new()
+            .warn_duplicate_includes()
+            .process(parse(content).unwrap())
+            .unwrap()
+            .report
+            .unwrap()
+            .warnings
+    };
+    assert!(
+        warnings("class A {\n    #include \"plain.hpp\"\n};\nclass B {\n    #include \"sub\\plain.hpp\"\n};\n").is_empty()
+    );
+    let duplicate =
+        warnings("class A {\n    #include \"plain.hpp\"\n};\nclass B {\n    #include \"sub/../plain.hpp\"\n};\n");
+    assert_eq!(duplicate.len(), 1);
+    assert_eq!(duplicate[0].code, Code::DuplicateInclude);
+}
+
+#[test]
+fn lint() {
+    let args = |extra: &[&str]| -> Vec<String> {
+        ["armalint", "lint", "tests/includes/duplicate.cpp"]
+            .iter()
+            .chain(extra)
+            .map(|s| s.to_string())
+            .collect()
+    };
+    armalint::execute(&args(&["--duplicate-includes"])).unwrap();
+    match armalint::execute(
+        &["armalint", "lint", "tests/includes/a.hpp"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>(),
+    ) {
+        Err(ArmaLintError::IncludeCycle(chain)) => assert_eq!(chain, vec!["tests/includes/a.hpp", "b.hpp", "a.hpp"]),
+        other => panic!("{:?}", other),
+    }
+}
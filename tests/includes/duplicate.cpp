@@ -0,0 +1,2 @@
+#include "plain.hpp"
+#include "plain.hpp"
@@ -0,0 +1,4 @@
+#ifndef GUARD_HPP
+#define GUARD_HPP
+#define SPEED 120
+#endif
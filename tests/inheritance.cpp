@@ -0,0 +1,37 @@
+class CfgVehicles {
+    class Car;
+    class Car_F: Car {
+        maxSpeed = 100;
+        fuelCapacity = 20;
+        sounds[] = {"engine"};
+        class Turrets {
+            class MainTurret {
+                gunner = "driver";
+            };
+        };
+        class HitPoints {
+            class HitEngine {
+                armor = 1;
+            };
+        };
+    };
+    class B_Car_F: Car_F {
+        maxSpeed = 120;
+        sounds[] += {"horn"};
+        class Turrets: Turrets {
+            class MainTurret: MainTurret {
+                weapons[] = {"gun"};
+            };
+        };
+        class HitPoints;
+    };
+    class Loop_A: Loop_B {
+        x = 1;
+    };
+    class Loop_B: Loop_A {
+        y = 1;
+    };
+    class Orphan: Missing {
+        z = 1;
+    };
+};
@@ -0,0 +1,134 @@
+use armalint::config::inheritance::InheritanceError;
+use armalint::config::simplify::{Array, ArrayElement, Class, Config, Entry};
+
+const FILE: &str = "tests/inheritance.cpp";
+const FILENAME: &str = "inheritance.cpp";
+
+fn config() -> Config {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    Config::from_ast(processed).unwrap()
+}
+
+#[test]
+fn properties() {
+    let resolved = config().resolve();
+    let car = resolved.class("cfgvehicles/b_car_f").unwrap();
+    assert_eq!(car.path, "CfgVehicles/B_Car_F");
+    assert_eq!(car.parent, Some("CfgVehicles/Car_F".to_string()));
+
+    let speed = car.property("maxSpeed").unwrap();
+    assert!(matches!(speed.value, Entry::Int(120)));
+    assert_eq!(speed.defined_in, "CfgVehicles/B_Car_F");
+    let fuel = car.property("FUELCAPACITY").unwrap();
+    assert!(matches!(fuel.value, Entry::Int(20)));
+    assert_eq!(fuel.defined_in, "CfgVehicles/Car_F");
+
+    match &car.property("sounds").unwrap().value {
+        Entry::Array(array) => assert_eq!(array.elements.len(), 2),
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(
+        resolved.class("CfgVehicles/Car_F").unwrap().parent,
+        Some("CfgVehicles/Car".to_string())
+    );
+}
+
+#[test]
+fn nested() {
+    let resolved = config().resolve();
+    let turret = resolved.class("CfgVehicles/B_Car_F/Turrets/MainTurret").unwrap();
+    assert_eq!(turret.parent, Some("CfgVehicles/Car_F/Turrets/MainTurret".to_string()));
+    assert_eq!(
+        turret.property("gunner").unwrap().defined_in,
+        "CfgVehicles/Car_F/Turrets/MainTurret"
+    );
+    assert!(turret.property("weapons").is_some());
+    // Declared with `class HitPoints;`, inherited unchanged
+    let engine = resolved.class("CfgVehicles/B_Car_F/HitPoints/HitEngine").unwrap();
+    assert_eq!(engine.path, "CfgVehicles/Car_F/HitPoints/HitEngine");
+}
+
+#[test]
+fn diagnostics() {
+    let resolved = config().resolve();
+    assert_eq!(resolved.diagnostics.len(), 2);
+    assert!(resolved.diagnostics.contains(&InheritanceError::Circular(vec![
+        "CfgVehicles/Loop_A".to_string(),
+        "CfgVehicles/Loop_B".to_string(),
+        "CfgVehicles/Loop_A".to_string(),
+    ])));
+    assert!(resolved.diagnostics.contains(&InheritanceError::MissingParent {
+        class: "CfgVehicles/Orphan".to_string(),
+        parent: "Missing".to_string(),
+    }));
+    assert!(resolved.class("CfgVehicles/Orphan").unwrap().parent.is_none());
+}
+
+#[test]
+fn deletion() {
+    let class = |parent: &str, entries: Vec<(String, Entry)>| Class {
+        parent: parent.to_string(),
+        external: false,
+        deletion: false,
+        entries,
+    };
+    let marker = |external: bool, deletion: bool| {
+        Entry::Class(Class {
+            parent: String::new(),
+            external,
+            deletion,
+            entries: Vec::new(),
+        })
+    };
+    let config = Config {
+        enums: Vec::new(),
+        root: class(
+            "",
+            vec![
+                (
+                    "Base".to_string(),
+                    Entry::Class(class(
+                        "",
+                        vec![
+                            (
+                                "Keep".to_string(),
+                                Entry::Class(class("", vec![("a".to_string(), Entry::Int(1))])),
+                            ),
+                            (
+                                "Remove".to_string(),
+                                Entry::Class(class("", vec![("b".to_string(), Entry::Int(2))])),
+                            ),
+                            (
+                                "list".to_string(),
+                                Entry::Array(Array {
+                                    expand: false,
+                                    elements: vec![ArrayElement::Int(1)],
+                                }),
+                            ),
+                        ],
+                    )),
+                ),
+                (
+                    "Child".to_string(),
+                    Entry::Class(class(
+                        "base",
+                        vec![
+                            ("Keep".to_string(), marker(true, false)),
+                            ("Remove".to_string(), marker(false, true)),
+                        ],
+                    )),
+                ),
+            ],
+        ),
+    };
+    let resolved = config.resolve();
+    assert!(resolved.diagnostics.is_empty());
+    let child = resolved.class("Child").unwrap();
+    assert_eq!(child.parent, Some("Base".to_string()));
+    assert_eq!(child.classes, vec![("Keep".to_string(), "Base/Keep".to_string())]);
+    assert!(resolved.class("Child/Remove").is_none());
+    assert!(child.property("list").is_some());
+}
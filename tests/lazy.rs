@@ -0,0 +1,83 @@
+use std::io::Cursor;
+
+use armalint::config::lazy::LazyConfig;
+use armalint::config::simplify::Config;
+use armalint::error::{ArmaLintError, RapifiedError};
+
+const FILE: &str = "tests/query.cpp";
+const FILENAME: &str = "query.cpp";
+
+fn config() -> Config {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    Config::from_ast(processed).unwrap()
+}
+
+fn lazy(config: &Config) -> LazyConfig<Cursor<Box<[u8]>>> {
+    LazyConfig::open(config.to_cursor().unwrap()).unwrap()
+}
+
+#[test]
+fn get() {
+    let config = lazy(&config());
+    assert!(!config.root().is_loaded());
+    // `CfgVehicles` is opened twice, both bodies are searched like the simplified config
+    assert_eq!(config.get("CfgVehicles/Car/maxSpeed").unwrap().unwrap().as_i32(), Some(100));
+    assert_eq!(
+        config.get("CfgVehicles/Truck/maxSpeed").unwrap().unwrap().as_f32(),
+        Some(60.0)
+    );
+    assert_eq!(
+        config.get("cfgvehicles/TANK/displayname").unwrap().unwrap().as_str(),
+        Some("Tank")
+    );
+    let weapons = config.get("CfgWeapons").unwrap().unwrap().as_class().unwrap();
+    assert!(!weapons.is_loaded());
+    let magazines = weapons.get("arifle_MX_F/magazines").unwrap().unwrap().as_array().unwrap();
+    assert_eq!(magazines.elements[1].as_str(), Some("30Rnd_65x39_caseless_mag_Tracer"));
+
+    // Only the classes along the path have been read
+    let katiba = weapons.entry("arifle_Katiba_F").unwrap().unwrap().as_class().unwrap();
+    assert!(weapons.is_loaded());
+    assert!(!katiba.is_loaded());
+    assert!(config.get("").unwrap().is_none());
+}
+
+#[test]
+fn wildcards() {
+    let config = lazy(&config());
+    let magazines = config.get_all("CfgWeapons/*/magazines").unwrap();
+    assert_eq!(
+        magazines.iter().map(|(path, _)| path.as_str()).collect::<Vec<&str>>(),
+        vec![
+            "CfgWeapons/arifle_MX_F/magazines",
+            "CfgWeapons/arifle_Katiba_F/magazines",
+            "CfgWeapons/hgun_P07_F/magazines",
+        ]
+    );
+    assert_eq!(config.get_all("*/*/displayName").unwrap().len(), 3);
+}
+
+#[test]
+fn load() {
+    let config = config();
+    let lazy = lazy(&config);
+    // Reading part of the config first does not change the result
+    lazy.get("CfgWeapons/hgun_P07_F/magazines").unwrap().unwrap();
+    assert_eq!(lazy.load().unwrap(), config);
+}
+
+#[test]
+fn cyclic_class_body() {
+    // class A contains a class B that points back to A
+    let mut bytes = b"\0raP\0\0\0\0\x08\0\0\0\0\0\0\0".to_vec();
+    bytes.extend_from_slice(&[0, 1, 0, b'A', 0, 25, 0, 0, 0, 0, 1, 0, b'B', 0, 25, 0, 0, 0]);
+    let config = LazyConfig::open(Cursor::new(bytes)).unwrap();
+    assert!(config.get("A").unwrap().is_some());
+    match config.get("A/B") {
+        Err(ArmaLintError::Rapified(RapifiedError::CyclicClassBody { offset: 30, target: 25 })) => {}
+        _ => panic!(),
+    }
+}
@@ -0,0 +1,4 @@
+#define speed 1
+#define SPEED 2
+#define SPEED 3
+value = SPEED;
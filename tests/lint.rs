@@ -0,0 +1,42 @@
+use armalint::error::ArmaLintError;
+
+fn lint(args: &[&str]) -> Result<(), ArmaLintError> {
+    let args: Vec<String> = ["armalint", "lint"].iter().chain(args).map(|s| s.to_string()).collect();
+    armalint::execute(&args)
+}
+
+#[test]
+fn warnings() {
+    lint(&["tests/lint.cpp"]).unwrap();
+    lint(&["tests/lint.cpp", "--max-warnings", "2"]).unwrap();
+    let err = lint(&["tests/lint.cpp", "--max-warnings", "1"]).unwrap_err();
+    assert!(matches!(
+        err,
+        ArmaLintError::Lint {
+            errors: 0,
+            warnings: 2,
+            max_warnings: Some(1)
+        }
+    ));
+    assert_eq!(err.exit_code(), 2);
+    assert_eq!(err.to_string(), "Found 2 warnings, at most 1 are allowed");
+    let err = lint(&["tests/lint.cpp", "--deny-warnings"]).unwrap_err();
+    assert_eq!(err.exit_code(), 2);
+    assert_eq!(err.to_string(), "Found 2 warnings, warnings are denied");
+}
+
+#[test]
+fn errors() {
+    let err = lint(&["tests/syntax.cpp", "--max-warnings", "10"]).unwrap_err();
+    assert!(matches!(err, ArmaLintError::Lint { errors: 6, .. }));
+    assert_eq!(err.exit_code(), 1);
+    assert_eq!(err.to_string(), "Found 6 errors and 0 warnings");
+}
+
+#[test]
+fn failure() {
+    assert_eq!(lint(&["tests/missing.cpp"]).unwrap_err().exit_code(), 3);
+    // Invalid arguments are failures too, not lint errors
+    assert_eq!(lint(&["tests/lint.cpp", "--max-warnings", "abc"]).unwrap_err().exit_code(), 3);
+    assert_eq!(lint(&["tests/lint.cpp", "--bogus"]).unwrap_err().exit_code(), 3);
+}
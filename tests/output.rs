@@ -0,0 +1,72 @@
+use armalint::config::{output, Diagnostic, PreProcessor};
+
+const CONTENT: &str = "#define speed 1\nclass A {\n    x = UNDEF(1);\n};\n";
+
+fn diagnostics() -> Vec<Diagnostic> {
+    let ast = armalint::config::parse("config.cpp", CONTENT).unwrap();
+    let report = PreProcessor::new().process(ast).unwrap().report.unwrap();
+    report.warnings.into_iter().chain(report.errors).collect()
+}
+
+#[test]
+fn json() {
+    let value: serde_json::Value = serde_json::from_str(&output::json(&diagnostics())).unwrap();
+    let first = &value[0];
+    assert_eq!(first["code"], "CFG006");
+    assert_eq!(first["severity"], "warning");
+    assert_eq!(first["file"], "config.cpp");
+    assert_eq!((&first["line"], &first["column"]), (&1.into(), &9.into()));
+    assert_eq!(first["end_column"], 14);
+    assert_eq!(first["help"], "use `SPEED`");
+    assert_eq!(first["fix"]["replacement"], "SPEED");
+    assert_eq!(value[1]["code"], "CFG002");
+    assert_eq!(value[1]["severity"], "error");
+    assert_eq!(value[1]["line"], 3);
+}
+
+#[test]
+fn sarif() {
+    let value: serde_json::Value = serde_json::from_str(&output::sarif(&diagnostics())).unwrap();
+    assert_eq!(value["version"], "2.1.0");
+    let run = &value["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "ArmaLint");
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1]["ruleId"], "CFG002");
+    assert_eq!(results[1]["level"], "error");
+    let rule = results[1]["ruleIndex"].as_u64().unwrap() as usize;
+    assert_eq!(run["tool"]["driver"]["rules"][rule]["id"], "CFG002");
+    let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!((&region["startLine"], &region["startColumn"]), (&3.into(), &9.into()));
+    assert_eq!(
+        results[0]["message"]["text"],
+        "Use of non-uppercase characters in define identifier\nhelp: use `SPEED`"
+    );
+}
+
+#[test]
+fn checkstyle() {
+    assert_eq!(
+        output::checkstyle(&diagnostics()),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="config.cpp">
+    <error line="1" column="9" severity="warning" message="Use of non-uppercase characters in define identifier&#10;help: use `SPEED`" source="armalint.CFG006"/>
+    <error line="3" column="9" severity="error" message="Call to undefined macro `UNDEF`" source="armalint.CFG002"/>
+  </file>
+</checkstyle>"#
+    );
+}
+
+#[test]
+fn format() {
+    let args = |format: &str| -> Vec<String> {
+        ["armalint", "lint", "tests/syntax.cpp", "--format", format]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    };
+    for format in &["human", "json", "sarif", "checkstyle"] {
+        assert!(armalint::execute(&args(format)).is_err());
+    }
+}
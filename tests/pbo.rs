@@ -0,0 +1,239 @@
+use std::io::Cursor;
+
+use armalint::config::simplify::Config;
+use armalint::config::{IncludeKind, IncludeResolver};
+use armalint::error::{ArmaLintError, PboError};
+use armalint::pbo::{Pbo, PboFile};
+
+const ADDON: &str = "tests/pbo/addon";
+const PREFIXED: &str = "tests/pbo/prefixed";
+
+fn args(args: &[&str]) -> Vec<String> {
+    std::iter::once("armalint")
+        .chain(args.iter().cloned())
+        .map(String::from)
+        .collect()
+}
+
+fn pbo() -> Pbo {
+    Pbo {
+        extensions: vec![("prefix".to_string(), "x\\test\\addons\\main".to_string())],
+        files: vec![
+            PboFile {
+                name: "config.bin".to_string(),
+                timestamp: 1_600_000_000,
+                data: b"\0raP".to_vec(),
+            },
+            PboFile {
+                name: "functions\\fnc_heal.sqf".to_string(),
+                timestamp: 0,
+                data: b"params [\"_unit\"];".to_vec(),
+            },
+        ],
+    }
+}
+
+fn bytes(pbo: &Pbo) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    pbo.write(&mut bytes).unwrap();
+    bytes
+}
+
+fn pbo_error(bytes: &[u8]) -> PboError {
+    match Pbo::read(Cursor::new(bytes)) {
+        Err(ArmaLintError::Pbo(err)) => err,
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let pbo = pbo();
+    let read = Pbo::read(Cursor::new(bytes(&pbo))).unwrap();
+    assert_eq!(read, pbo);
+    assert_eq!(read.extension("PREFIX"), Some("x\\test\\addons\\main"));
+    assert!(read.file("functions/FNC_heal.sqf").is_some());
+}
+
+#[test]
+fn checksum() {
+    let mut bytes = bytes(&pbo());
+    let data = bytes.len() - 22;
+    bytes[data] ^= 1;
+    assert_eq!(pbo_error(&bytes), PboError::ChecksumMismatch);
+    // Archives without a checksum are still read
+    bytes[data] ^= 1;
+    bytes.truncate(bytes.len() - 21);
+    assert_eq!(Pbo::read(Cursor::new(&bytes)).unwrap(), pbo());
+}
+
+#[test]
+fn truncated() {
+    let bytes = bytes(&pbo());
+    for len in 0..bytes.len() - 21 {
+        match pbo_error(&bytes[..len]) {
+            PboError::UnexpectedEnd { .. } => {}
+            other => panic!("{} {:?}", len, other),
+        }
+    }
+}
+
+#[test]
+fn compressed() {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"\0sreV");
+    bytes.extend_from_slice(&[0; 16]);
+    bytes.extend_from_slice(b"\0a.txt\0srpC\x09\0\0\0\0\0\0\0\0\0\0\0\x0a\0\0\0");
+    bytes.extend_from_slice(&[0; 21]);
+    bytes.extend_from_slice(&[0b0000_0111, b'a', b'b', b'c', 3, 3, 0x72, 0x03, 0, 0]);
+    let pbo = Pbo::read(Cursor::new(&bytes)).unwrap();
+    assert_eq!(pbo.file("a.txt").unwrap().data, b"abcabcabc");
+
+    // The checksum of the compressed data is wrong
+    let last = bytes.len() - 4;
+    bytes[last] = 0;
+    assert_eq!(
+        pbo_error(&bytes),
+        PboError::InvalidCompression {
+            file: "a.txt".to_string()
+        }
+    );
+}
+
+#[test]
+fn pack_and_unpack() {
+    let temp = tempfile::tempdir().unwrap();
+    let output = temp.path().join("pack.pbo");
+    armalint::execute(&args(&["pack", ADDON, output.to_str().unwrap()])).unwrap();
+    armalint::execute(&args(&["list", output.to_str().unwrap()])).unwrap();
+
+    let pbo = Pbo::read(std::fs::File::open(&output).unwrap()).unwrap();
+    assert_eq!(pbo.extension("prefix"), Some("x\\test\\addons\\main"));
+    assert_eq!(pbo.extension("version"), Some("1.0"));
+    assert_eq!(
+        pbo.files.iter().map(|f| f.name.as_str()).collect::<Vec<&str>>(),
+        vec!["config.bin", "functions\\fnc_heal.sqf"]
+    );
+    let config = Config::read_rapified(&mut Cursor::new(&pbo.file("config.bin").unwrap().data)).unwrap();
+    assert!(config.get("CfgPatches/test_main/requiredVersion").is_some());
+
+    let dir = temp.path().join("unpack");
+    armalint::execute(&args(&["unpack", output.to_str().unwrap(), dir.to_str().unwrap()])).unwrap();
+    assert_eq!(
+        std::fs::read(dir.join("functions").join("fnc_heal.sqf")).unwrap(),
+        std::fs::read("tests/pbo/addon/functions/fnc_heal.sqf").unwrap()
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("$PBOPREFIX$")).unwrap(),
+        "x\\test\\addons\\main\nversion=1.0\n"
+    );
+}
+
+#[test]
+fn pack_without_rapify() {
+    let temp = tempfile::tempdir().unwrap();
+    let output = temp.path().join("pack_cpp.pbo");
+    armalint::execute(&args(&[
+        "pack",
+        ADDON,
+        output.to_str().unwrap(),
+        "--no-rapify",
+        "--prefix",
+        "x\\other",
+    ]))
+    .unwrap();
+    let pbo = Pbo::read(std::fs::File::open(&output).unwrap()).unwrap();
+    assert_eq!(pbo.extensions[0], ("prefix".to_string(), "x\\other".to_string()));
+    assert!(pbo.file("config.cpp").is_some());
+}
+
+#[test]
+fn pack_with_prefix() {
+    // The config includes its own files through the prefix given on the command line
+    let temp = tempfile::tempdir().unwrap();
+    let output = temp.path().join("prefixed.pbo");
+    assert!(armalint::execute(&args(&["pack", PREFIXED, output.to_str().unwrap()])).is_err());
+    armalint::execute(&args(&["pack", PREFIXED, output.to_str().unwrap(), "--prefix", "x\\other"])).unwrap();
+    let pbo = Pbo::read(std::fs::File::open(&output).unwrap()).unwrap();
+    assert_eq!(pbo.extension("prefix"), Some("x\\other"));
+    let config = Config::read_rapified(&mut Cursor::new(&pbo.file("config.bin").unwrap().data)).unwrap();
+    assert_eq!(config.get("CfgPatches/other_main/name").unwrap().as_str(), Some("other"));
+}
+
+#[test]
+fn unpack_outside() {
+    for name in &["..\\evil.sqf", "\\evil.sqf", "a\\..\\..\\evil.sqf", "C:\\evil.sqf"] {
+        let mut pbo = pbo();
+        pbo.files[1].name = name.to_string();
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("unpack_outside");
+        match armalint::pbo::Pbo::read(Cursor::new(bytes(&pbo))) {
+            Ok(read) => assert!(armalint::commands::Unpack::unpack(&read, &dir).is_err()),
+            Err(err) => panic!("{}", err),
+        }
+        assert!(!dir.exists());
+    }
+}
+
+fn source_pbo(config: &str) -> Pbo {
+    let file = |name: &str, data: &str| PboFile {
+        name: name.to_string(),
+        timestamp: 0,
+        data: data.as_bytes().to_vec(),
+    };
+    Pbo {
+        extensions: vec![("prefix".to_string(), "x\\test\\addons\\main".to_string())],
+        files: vec![
+            file("config.cpp", config),
+            file(
+                "script_component.hpp",
+                "#include \"\\x\\test\\addons\\main\\include\\macros.hpp\"\n",
+            ),
+            file("include\\macros.hpp", "#define QUOTE(x) #x\n"),
+        ],
+    }
+}
+
+#[test]
+fn include() {
+    let pbo = source_pbo("");
+    let wd = std::path::Path::new("x/test/addons/main");
+    let (content, dir) = pbo.resolve("script_component.hpp", wd, IncludeKind::Quoted).unwrap();
+    assert!(content.starts_with("#include"));
+    assert_eq!(dir, std::path::PathBuf::from("x/test/addons/main"));
+    let (_, dir) = pbo
+        .resolve("\\X\\Test\\addons\\main\\include\\MACROS.hpp", wd, IncludeKind::Quoted)
+        .unwrap();
+    assert_eq!(dir, std::path::PathBuf::from("x/test/addons/main/include"));
+    assert!(pbo
+        .resolve("include/../script_component.hpp", wd, IncludeKind::Quoted)
+        .is_ok());
+    assert!(pbo.resolve("missing.hpp", wd, IncludeKind::Quoted).is_err());
+}
+
+#[test]
+fn lint() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("lint.pbo");
+    let config = "#include \"script_component.hpp\"\nclass CfgPatches {\n    class test_main {\n        name = QUOTE(main);\n    };\n};\n";
+    let mut pbo = source_pbo(config);
+    pbo.files.push(PboFile {
+        name: "config.bin".to_string(),
+        timestamp: 0,
+        data: {
+            let mut data = Vec::new();
+            let ast = armalint::config::parse("config.cpp", "class A {};").unwrap();
+            let processed = armalint::config::PreProcessor::new().process(ast).unwrap();
+            Config::from_ast(processed).unwrap().write_rapified(&mut data).unwrap();
+            data
+        },
+    });
+    pbo.write(&mut std::fs::File::create(&path).unwrap()).unwrap();
+    armalint::execute(&args(&["lint", path.to_str().unwrap()])).unwrap();
+
+    let path = temp.path().join("lint_broken.pbo");
+    source_pbo("#include \"missing.hpp\"\n")
+        .write(&mut std::fs::File::create(&path).unwrap())
+        .unwrap();
+    assert!(armalint::execute(&args(&["lint", path.to_str().unwrap()])).is_err());
+}
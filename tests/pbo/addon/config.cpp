@@ -0,0 +1,6 @@
+class CfgPatches {
+    class test_main {
+        units[] = {};
+        requiredVersion = 2.0;
+    };
+};
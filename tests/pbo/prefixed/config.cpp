@@ -0,0 +1,6 @@
+#include "\x\other\script_component.hpp"
+class CfgPatches {
+    class other_main {
+        name = COMPONENT;
+    };
+};
@@ -0,0 +1 @@
+#define COMPONENT "other"
@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+
+use armalint::commands::{Project, Rapify, Sources};
+use armalint::config::render::{BracketStyle, IndentationType};
+use armalint::config::{Code, Severity};
+
+#[test]
+fn load() {
+    let project = Project::load(Path::new("tests/project/addons/main")).unwrap();
+    assert_eq!(project.file, Some(PathBuf::from("tests/project/armalint.toml")));
+    assert_eq!(project.root, PathBuf::from("tests/project"));
+    assert_eq!(project.rules.get(&Code::UnquotedValue), Some(&None));
+    assert_eq!(project.rules.get(&Code::NonUppercaseDefine), Some(&Some(Severity::Error)));
+    assert!(project.render.bracket_style == BracketStyle::Linux);
+    assert_eq!(project.path("addons"), PathBuf::from("tests/project/addons"));
+
+    // `.armalint.toml` is found too, the closest project file is used
+    let nested = Project::load(Path::new("tests/project/nested")).unwrap();
+    assert_eq!(nested.file, Some(PathBuf::from("tests/project/nested/.armalint.toml")));
+    assert!(nested.render.indentation_type == IndentationType::Tab);
+    assert!(nested.rules.is_empty());
+}
+
+#[test]
+fn config() {
+    let project = Project::load(Path::new("tests/project")).unwrap();
+    let path = Path::new("tests/project/addons/main/config.cpp");
+    let resolver = project.with_includes(Rapify::resolver(path, &[]).unwrap()).unwrap();
+    let config = Rapify::config(path, &resolver, &project, Path::new("config.bin")).unwrap();
+    assert_eq!(config.get("CfgPatches/main/version").unwrap().as_i32(), Some(3));
+    assert_eq!(config.get("CfgPatches/main/name").unwrap().as_str(), Some("main"));
+    assert_eq!(config.get("CfgPatches/main/author").unwrap().as_str(), Some("ArmaLint"));
+    assert_eq!(config.get("CfgPatches/main/debug").unwrap().as_i32(), Some(1));
+}
+
+#[test]
+fn rules_do_not_write_errors() {
+    // Turning the errors off hides them, the config is still not written
+    let project = Project::from_toml("[rules]\nCFG002 = \"off\"\nCFG003 = \"off\"", Path::new("tests/project")).unwrap();
+    let path = Path::new("tests/project/broken.cpp");
+    let resolver = Rapify::resolver(path, &[]).unwrap();
+    let err = Rapify::config(path, &resolver, &project, Path::new("broken.bin"))
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "Not writing `broken.bin`, the config has 2 errors");
+}
+
+#[test]
+fn files() {
+    let project = Project::load(Path::new("tests/project")).unwrap();
+    let paths: Vec<String> = project.files.iter().map(|f| project.path(f).display().to_string()).collect();
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    assert_eq!(paths, vec!["addons"]);
+    assert_eq!(
+        Sources::find_excluding(&["tests/project/addons"], &project.root, &project.exclude).unwrap(),
+        vec![
+            PathBuf::from("tests/project/addons/main/config.cpp"),
+            PathBuf::from("tests/project/addons/main/script_component.hpp")
+        ]
+    );
+}
+
+#[test]
+fn invalid() {
+    let invalid = |content: &str| Project::from_toml(content, Path::new(".")).err().unwrap().to_string();
+    assert!(invalid("[rules]\nCFG999 = \"off\"").contains("unknown rule `CFG999`"));
+    assert!(invalid("[rules]\nCFG001 = \"fatal\"").contains("invalid severity `fatal`"));
+    assert!(invalid("[defines]\nLIST = [1, 2]").contains("invalid value"));
+    assert!(invalid("[lint]\nstrict = true").contains("unknown field `lint`"));
+
+    // Errors name the file that was loaded
+    let err = Project::load(Path::new("tests/project/invalid")).err().unwrap().to_string();
+    assert!(err.contains("tests/project/invalid/.armalint.toml: invalid `bracket_style` value `k&r`"));
+}
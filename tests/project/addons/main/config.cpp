@@ -0,0 +1,13 @@
+#include "\x\project\addons\main\script_component.hpp"
+#include "common.hpp"
+
+class CfgPatches {
+    class main {
+        version = VERSION;
+        name = QUOTE(COMPONENT);
+        author = AUTHOR;
+#ifdef DEBUG_MODE_FULL
+        debug = 1;
+#endif
+    };
+};
@@ -0,0 +1,3 @@
+class Old {
+    value = 1
+};
@@ -0,0 +1,3 @@
+#define FOO(a) a
+FOO(1,2);
+BAR(1);
@@ -0,0 +1 @@
+#define AUTHOR "ArmaLint"
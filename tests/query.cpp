@@ -0,0 +1,29 @@
+class CfgVehicles {
+    class Car {
+        displayName = "Car";
+        maxSpeed = 100;
+    };
+    class Truck {
+        displayName = "Truck";
+        maxSpeed = 80.5;
+    };
+};
+class CfgWeapons {
+    class arifle_MX_F {
+        magazines[] = {"30Rnd_65x39_caseless_mag", "30Rnd_65x39_caseless_mag_Tracer"};
+    };
+    class arifle_Katiba_F {
+        magazines[] = {"30Rnd_65x39_caseless_green"};
+    };
+    class hgun_P07_F {
+        magazines[] = {"16Rnd_9x21_Mag"};
+    };
+};
+class CfgVehicles {
+    class Truck {
+        maxSpeed = 60;
+    };
+    class Tank {
+        displayName = "Tank";
+    };
+};
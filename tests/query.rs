@@ -0,0 +1,76 @@
+use armalint::config::simplify::Config;
+
+const FILE: &str = "tests/query.cpp";
+const FILENAME: &str = "query.cpp";
+
+fn config() -> Config {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let ast = armalint::config::parse(FILENAME, &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    Config::from_ast(processed).unwrap()
+}
+
+#[test]
+fn get() {
+    let config = config();
+    // The second `CfgVehicles` is merged into the first, its properties win
+    assert_eq!(config.get("CfgVehicles/Car/maxSpeed").unwrap().as_i32(), Some(100));
+    assert_eq!(config.get("CfgVehicles/Truck/maxSpeed").unwrap().as_f32(), Some(60.0));
+    assert_eq!(config.get("CfgVehicles/Truck/displayName").unwrap().as_str(), Some("Truck"));
+    assert_eq!(config.get("cfgvehicles/TANK/displayname").unwrap().as_str(), Some("Tank"));
+    assert_eq!(
+        config
+            .get("CfgWeapons/hgun_P07_F/magazines")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .elements
+            .len(),
+        1
+    );
+    assert!(config.get("CfgWeapons/hgun_P07_F/magazines").unwrap().as_str().is_none());
+    assert!(config.get("CfgWeapons/Missing").is_none());
+    assert!(config.get("").is_none());
+
+    let class = config.get("CfgWeapons").unwrap().as_class().unwrap();
+    let magazines = class.get("arifle_MX_F/magazines").unwrap().as_array().unwrap();
+    assert_eq!(magazines.elements[1].as_str(), Some("30Rnd_65x39_caseless_mag_Tracer"));
+}
+
+#[test]
+fn wildcards() {
+    let config = config();
+    let magazines = config.get_all("CfgWeapons/*/magazines");
+    assert_eq!(
+        magazines.iter().map(|(path, _)| path.as_str()).collect::<Vec<&str>>(),
+        vec![
+            "CfgWeapons/arifle_MX_F/magazines",
+            "CfgWeapons/arifle_Katiba_F/magazines",
+            "CfgWeapons/hgun_P07_F/magazines",
+        ]
+    );
+    assert_eq!(config.get_all("CfgWeapons/ARIFLE_*").len(), 2);
+    assert!(config.get("CfgWeapons/*_F/magazines").is_some());
+    assert_eq!(
+        config
+            .get_all("CfgVehicles/*")
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["CfgVehicles/Car", "CfgVehicles/Truck", "CfgVehicles/Tank"]
+    );
+    assert_eq!(config.get_all("*/*/displayName").len(), 3);
+}
+
+#[test]
+fn numbers() {
+    let content = "speed = 100;\nratio = 0.5;\n";
+    let ast = armalint::config::parse(FILENAME, content).unwrap();
+    let processed = armalint::config::PreProcessor::new().process(ast).unwrap();
+    let config = Config::from_ast(processed).unwrap();
+    assert_eq!(config.get("speed").unwrap().as_f32(), Some(100.0));
+    assert_eq!(config.get("speed").unwrap().as_i32(), Some(100));
+    assert_eq!(config.get("ratio").unwrap().as_f32(), Some(0.5));
+    assert!(config.get("ratio").unwrap().as_i32().is_none());
+}
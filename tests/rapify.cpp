@@ -0,0 +1,6 @@
+#include "\x\test\macros.hpp"
+class CfgVehicles {
+    class Car {
+        maxSpeed = SPEED;
+    };
+};
@@ -0,0 +1,41 @@
+use armalint::config::simplify::{Config, Entry};
+
+const FILE: &str = "tests/rapify.cpp";
+
+fn args(args: &[&str]) -> Vec<String> {
+    std::iter::once("armalint")
+        .chain(args.iter().cloned())
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn rapify() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rapify.bin");
+    armalint::execute(&args(&["rapify", FILE, output.to_str().unwrap(), "-i", "tests/include"])).unwrap();
+    let config = Config::read_rapified(&mut std::fs::File::open(&output).unwrap()).unwrap();
+    let speed = match config.root.entries[0].1 {
+        Entry::Class(ref c) => match c.entries[0].1 {
+            Entry::Class(ref c) => c.entries[0].1.clone(),
+            _ => panic!(),
+        },
+        _ => panic!(),
+    };
+    assert!(matches!(speed, Entry::Int(120)));
+}
+
+#[test]
+fn refuse_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rapify_errors.bin");
+    assert!(armalint::execute(&args(&["rapify", "tests/rapify_errors.cpp", output.to_str().unwrap()])).is_err());
+    assert!(!output.exists());
+}
+
+#[test]
+fn missing_include() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("rapify_include.bin");
+    assert!(armalint::execute(&args(&["rapify", FILE, output.to_str().unwrap()])).is_err());
+}
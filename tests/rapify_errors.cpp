@@ -0,0 +1,3 @@
+class Car {
+    maxSpeed = MISSING(120);
+};
@@ -0,0 +1,188 @@
+use std::io::Cursor;
+
+use armalint::config::simplify::{Config, Entry};
+use armalint::error::{ArmaLintError, RapifiedError};
+
+fn seed() -> Vec<u8> {
+    let content = std::fs::read_to_string("tests/derapify.cpp").unwrap();
+    let ast = armalint::config::parse("derapify.cpp", &content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    Config::from_ast(processed)
+        .unwrap()
+        .to_cursor()
+        .unwrap()
+        .into_inner()
+        .into_vec()
+}
+
+fn read(bytes: &[u8]) -> Result<Config, ArmaLintError> {
+    Config::read_rapified(&mut Cursor::new(bytes))
+}
+
+fn rapified_error(bytes: &[u8]) -> RapifiedError {
+    match read(bytes) {
+        Err(ArmaLintError::Rapified(err)) => err,
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+}
+
+/// A header followed by the root class body, without enums
+fn config(root: &[u8]) -> Vec<u8> {
+    let mut bytes = b"\0raP\0\0\0\0\x08\0\0\0\0\0\0\0".to_vec();
+    bytes.extend_from_slice(root);
+    bytes
+}
+
+#[test]
+fn seed_is_valid() {
+    assert!(read(&seed()).is_ok());
+}
+
+#[test]
+fn truncated() {
+    let seed = seed();
+    for len in 0..seed.len() {
+        rapified_error(&seed[..len]);
+    }
+}
+
+#[test]
+fn corrupted_bytes() {
+    let seed = seed();
+    for position in 0..seed.len() {
+        for value in &[0x00, 0xff, 0x80, 0x10, 0x7f, seed[position] ^ 1] {
+            let mut bytes = seed.clone();
+            bytes[position] = *value;
+            let _ = read(&bytes);
+        }
+    }
+}
+
+#[test]
+fn random_mutations() {
+    let seed = seed();
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..5000 {
+        let mut bytes = seed.clone();
+        for _ in 0..=next() % 8 {
+            let position = (next() % bytes.len() as u64) as usize;
+            bytes[position] = next() as u8;
+        }
+        if next() % 4 == 0 {
+            bytes.truncate((next() % bytes.len() as u64) as usize);
+        }
+        let _ = read(&bytes);
+    }
+}
+
+#[test]
+fn invalid_magic() {
+    assert_eq!(
+        rapified_error(b"\0rap\0\0\0\0\x08\0\0\0\0\0\0\0\0\0"),
+        RapifiedError::InvalidMagic
+    );
+}
+
+#[test]
+fn cyclic_class_body() {
+    // class A points back to the root
+    assert_eq!(
+        rapified_error(&config(&[0, 1, 0, b'A', 0, 16, 0, 0, 0])),
+        RapifiedError::CyclicClassBody { offset: 21, target: 16 }
+    );
+    // class A contains a class B that points back to A
+    assert_eq!(
+        rapified_error(&config(&[0, 1, 0, b'A', 0, 25, 0, 0, 0, 0, 1, 0, b'B', 0, 25, 0, 0, 0])),
+        RapifiedError::CyclicClassBody { offset: 30, target: 25 }
+    );
+}
+
+#[test]
+fn shared_class_body() {
+    assert_eq!(
+        rapified_error(&config(&[0, 2, 0, b'A', 0, 32, 0, 0, 0, 0, b'B', 0, 32, 0, 0, 0, 0, 0])),
+        RapifiedError::SharedClassBody { offset: 28, target: 32 }
+    );
+}
+
+#[test]
+fn invalid_offset() {
+    assert_eq!(
+        rapified_error(&config(&[0, 1, 0, b'A', 0, 0xff, 0, 0, 0])),
+        RapifiedError::InvalidOffset { offset: 21, target: 255 }
+    );
+}
+
+#[test]
+fn huge_count() {
+    assert_eq!(
+        rapified_error(&config(&[0, 0xff, 0xff, 0xff, 0xff, 0x0f])),
+        RapifiedError::InvalidCount {
+            offset: 17,
+            count: u32::MAX
+        }
+    );
+    assert_eq!(
+        rapified_error(&config(&[0, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01])),
+        RapifiedError::InvalidCompressedInt { offset: 17 }
+    );
+}
+
+#[test]
+fn invalid_types() {
+    assert_eq!(
+        rapified_error(&config(&[0, 1, 9, b'A', 0])),
+        RapifiedError::InvalidEntryType {
+            offset: 18,
+            entry_type: 9
+        }
+    );
+    assert_eq!(
+        rapified_error(&config(&[0, 1, 1, 7, b'A', 0, 0])),
+        RapifiedError::InvalidSubtype { offset: 19, subtype: 7 }
+    );
+    assert_eq!(
+        rapified_error(&config(&[0, 1, 2, b'A', 0, 1, 4, 0])),
+        RapifiedError::InvalidArrayElement {
+            offset: 22,
+            element_type: 4
+        }
+    );
+}
+
+#[test]
+fn deeply_nested_arrays() {
+    let mut root = vec![0, 1, 2, b'a', 0];
+    for _ in 0..100_000 {
+        root.extend_from_slice(&[1, 3]);
+    }
+    root.push(0);
+    match rapified_error(&config(&root)) {
+        RapifiedError::TooDeep { .. } => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn non_utf8_names() {
+    let root = [0, 1, 1, 0, 0xe9, b'a', 0, 0xff, 0];
+    let mut bytes = b"\0raP\0\0\0\0\x08\0\0\0\x19\0\0\0".to_vec();
+    bytes.extend_from_slice(&root);
+    bytes.extend_from_slice(&[0, 0, 0, 0]);
+
+    let config = read(&bytes).unwrap();
+    let (name, value) = &config.root.entries[0];
+    assert_eq!(name, "\u{efe9}a");
+    match value {
+        Entry::Str(s) => assert_eq!(s, "\u{efff}"),
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(config.to_cursor().unwrap().into_inner().into_vec(), bytes);
+}
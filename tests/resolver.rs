@@ -0,0 +1,140 @@
+use std::path::{Path, PathBuf};
+
+use armalint::config::simplify::Config;
+use std::cell::Cell;
+
+use armalint::config::{ChainResolver, FsResolver, IncludeKind, IncludeResolver, MemoryResolver};
+use armalint::error::ArmaLintError;
+
+const ROOT: &str = "tests/resolver";
+const ADDON: &str = "tests/resolver/addons/main";
+
+fn args(args: &[&str]) -> Vec<String> {
+    std::iter::once("armalint")
+        .chain(args.iter().cloned())
+        .map(String::from)
+        .collect()
+}
+
+fn resolver() -> FsResolver {
+    FsResolver::new()
+        .prefix_files(Path::new(ROOT))
+        .unwrap()
+        .search_path(Path::new(ROOT).join("include"))
+}
+
+#[test]
+fn prefix() {
+    let found = resolver().find(
+        "\\Z\\ACE\\addons\\main\\script_component.hpp",
+        Path::new("."),
+        IncludeKind::Quoted,
+    );
+    assert_eq!(found, Some(Path::new(ADDON).join("Script_Component.hpp")));
+
+    let explicit = FsResolver::new().prefix("x/other", ADDON);
+    let (content, wd) = explicit
+        .resolve("/x/other/SCRIPT_COMPONENT.HPP", Path::new("."), IncludeKind::System)
+        .unwrap();
+    assert_eq!(content, "#define COMPONENT main\n");
+    assert_eq!(wd, PathBuf::from(ADDON));
+}
+
+#[test]
+fn quoted_and_system() {
+    let resolver = resolver();
+    let wd = Path::new(ADDON);
+    let (quoted, _) = resolver.resolve("script_component.hpp", wd, IncludeKind::Quoted).unwrap();
+    assert_eq!(quoted, "#define COMPONENT main\n");
+    let (system, _) = resolver.resolve("script_component.hpp", wd, IncludeKind::System).unwrap();
+    assert_eq!(system, "#define COMPONENT include\n");
+    assert!(resolver.resolve("missing.hpp", wd, IncludeKind::Quoted).is_err());
+}
+
+#[test]
+fn rapify_addon() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("resolver.bin");
+    let config = Path::new(ADDON).join("config.cpp");
+    armalint::execute(&args(&["rapify", config.to_str().unwrap(), output.to_str().unwrap()])).unwrap();
+    let config = Config::read_rapified(&mut std::fs::File::open(&output).unwrap()).unwrap();
+    assert!(config.get("CfgPatches/main/units").is_some());
+}
+
+#[test]
+fn memory() {
+    let resolver = MemoryResolver::new()
+        .file("\\x\\test\\script_component.hpp", "#include \"macros.hpp\"\n")
+        .file("x/test/Macros.hpp", "#define SPEED 120\n")
+        .file("macros.hpp", "#define SPEED 0\n");
+    let (_, wd) = resolver
+        .resolve("\\X\\Test\\script_component.hpp", Path::new(""), IncludeKind::Quoted)
+        .unwrap();
+    assert_eq!(wd, PathBuf::from("x/test"));
+    let (quoted, _) = resolver.resolve("macros.hpp", &wd, IncludeKind::Quoted).unwrap();
+    assert_eq!(quoted, "#define SPEED 120\n");
+    let (system, _) = resolver.resolve("macros.hpp", &wd, IncludeKind::System).unwrap();
+    assert_eq!(system, "#define SPEED 0\n");
+
+    let ast = armalint::config::parse_with_resolver(
+        "config.cpp",
+        PathBuf::new(),
+        "#include \"\\x\\test\\script_component.hpp\"\nspeed = SPEED;\n",
+        &resolver,
+    )
+    .unwrap();
+    let processed = armalint::config::PreProcessor::new().process(ast).unwrap();
+    let config = Config::from_ast(processed).unwrap();
+    assert!(matches!(
+        config.get("speed"),
+        Some(armalint::config::simplify::Entry::Int(120))
+    ));
+}
+
+/// Counts the files it is asked for
+struct Counting<'a> {
+    inner: &'a dyn IncludeResolver,
+    count: Cell<usize>,
+}
+
+impl IncludeResolver for Counting<'_> {
+    fn resolve(&self, filename: &str, wd: &Path, kind: IncludeKind) -> Result<(String, PathBuf), ArmaLintError> {
+        self.count.set(self.count.get() + 1);
+        self.inner.resolve(filename, wd, kind)
+    }
+}
+
+#[test]
+fn chain() {
+    let memory = MemoryResolver::new().file("memory.hpp", "#include <script_component.hpp>\n");
+    let disk = resolver();
+    let chain = ChainResolver::new().with(&memory).with(&disk);
+    let counting = Counting {
+        inner: &chain,
+        count: Cell::new(0),
+    };
+    let ast = armalint::config::parse_with_resolver(
+        "config.cpp",
+        PathBuf::from(ADDON),
+        "#include \"memory.hpp\"\n#include \"script_component.hpp\"\n",
+        &counting,
+    )
+    .unwrap();
+    assert_eq!(counting.count.get(), 3);
+    assert_eq!(ast.includes.len(), 3);
+    assert!(matches!(
+        chain.resolve("missing.hpp", Path::new(ADDON), IncludeKind::Quoted),
+        Err(ArmaLintError::IncludeNotFound(_))
+    ));
+
+    // Only a file that is not found moves on to the next resolver
+    let denied = |_: &str, _: &Path, _: IncludeKind| -> Result<(String, PathBuf), ArmaLintError> {
+        Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied).into())
+    };
+    let chain = ChainResolver::new().with(&memory).with(&denied).with(&disk);
+    match chain.resolve("script_component.hpp", Path::new(ADDON), IncludeKind::Quoted) {
+        Err(ArmaLintError::IO(err)) => assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied),
+        other => panic!("{:?}", other),
+    }
+    assert!(chain.resolve("memory.hpp", Path::new(ADDON), IncludeKind::Quoted).is_ok());
+}
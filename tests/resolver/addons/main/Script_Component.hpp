@@ -0,0 +1 @@
+#define COMPONENT main
@@ -0,0 +1,7 @@
+#include "\z\ace\addons\main\script_component.hpp"
+
+class CfgPatches {
+    class COMPONENT {
+        units[] = {};
+    };
+};
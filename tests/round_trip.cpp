@@ -0,0 +1,10 @@
+class CfgVehicles {
+    class Car;
+    class Truck: Car {
+        delete Turrets;
+        class Turrets {
+            class MainTurret {};
+        };
+    };
+    delete Plane;
+};
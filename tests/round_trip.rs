@@ -0,0 +1,111 @@
+use armalint::config::simplify::{Array, ArrayElement, Class, Config, Entry};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+const FILE: &str = "tests/round_trip.cpp";
+const FILENAME: &str = "round_trip.cpp";
+
+fn simplify(content: &str) -> Config {
+    let ast = armalint::config::parse(FILENAME, content).unwrap();
+    let mut preprocessor = armalint::config::PreProcessor::new();
+    let processed = preprocessor.process(ast).unwrap();
+    Config::from_ast(processed).unwrap()
+}
+
+fn round_trip(config: &Config) -> Config {
+    let mut cursor = config.to_cursor().unwrap();
+    cursor.set_position(0);
+    Config::read_rapified(&mut cursor).unwrap()
+}
+
+#[test]
+fn deletion() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let config = simplify(&content);
+    let vehicles = config.get("CfgVehicles").and_then(Entry::as_class).unwrap();
+    assert!(matches!(
+        vehicles.entry("Car"),
+        Some(Entry::Class(Class {
+            external: true,
+            deletion: false,
+            ..
+        }))
+    ));
+    assert!(matches!(
+        vehicles.entry("Plane"),
+        Some(Entry::Class(Class {
+            external: false,
+            deletion: true,
+            ..
+        }))
+    ));
+    assert_eq!(round_trip(&config), config);
+}
+
+/// Strings without the terminator and without the characters standing in for non-UTF-8 bytes
+fn string() -> impl Strategy<Value = String> {
+    vec(
+        any::<char>().prop_filter("not representable", |c| *c != '\0' && !('\u{ef80}'..='\u{efff}').contains(c)),
+        0..8,
+    )
+    .prop_map(|chars| chars.into_iter().collect())
+}
+
+fn float() -> impl Strategy<Value = f32> {
+    any::<f32>().prop_filter("NaN is not equal to itself", |f| !f.is_nan())
+}
+
+fn element() -> impl Strategy<Value = ArrayElement> {
+    prop_oneof![
+        string().prop_map(ArrayElement::Str),
+        float().prop_map(ArrayElement::Float),
+        any::<i32>().prop_map(ArrayElement::Int),
+    ]
+    .prop_recursive(3, 16, 4, |inner| {
+        vec(inner, 0..4).prop_map(|elements| ArrayElement::Array(Array { expand: false, elements }))
+    })
+}
+
+fn entry() -> impl Strategy<Value = Entry> {
+    let leaf = prop_oneof![
+        string().prop_map(Entry::Str),
+        float().prop_map(Entry::Float),
+        any::<i32>().prop_map(Entry::Int),
+        (any::<bool>(), vec(element(), 0..4)).prop_map(|(expand, elements)| Entry::Array(Array { expand, elements })),
+        (any::<bool>()).prop_map(|deletion| Entry::Class(Class {
+            parent: String::new(),
+            external: !deletion,
+            deletion,
+            entries: Vec::new(),
+        })),
+    ];
+    leaf.prop_recursive(4, 32, 6, |inner| {
+        (string(), vec((string(), inner), 0..6)).prop_map(|(parent, entries)| {
+            Entry::Class(Class {
+                parent,
+                external: false,
+                deletion: false,
+                entries,
+            })
+        })
+    })
+}
+
+fn config() -> impl Strategy<Value = Config> {
+    (vec((string(), entry()), 0..8), vec((string(), any::<i32>()), 0..4)).prop_map(|(entries, enums)| Config {
+        root: Class {
+            parent: String::new(),
+            external: false,
+            deletion: false,
+            entries,
+        },
+        enums,
+    })
+}
+
+proptest! {
+    #[test]
+    fn random_configs(config in config()) {
+        prop_assert_eq!(round_trip(&config), config);
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use armalint::commands::Sources;
+use armalint::error::ArmaLintError;
+
+fn lint(args: &[&str]) -> Result<(), ArmaLintError> {
+    let args: Vec<String> = ["armalint", "lint"].iter().chain(args).map(|s| s.to_string()).collect();
+    armalint::execute(&args)
+}
+
+fn paths(paths: &[&str]) -> Vec<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn directory() {
+    // `old` is in `.armalintignore`, `.hidden` is hidden and `readme.txt` is not a config
+    assert_eq!(
+        Sources::find(&["tests/sources/addons"]).unwrap(),
+        paths(&[
+            "tests/sources/addons/main/config.cpp",
+            "tests/sources/addons/main/ui/dialog.hpp"
+        ])
+    );
+}
+
+#[test]
+fn globs() {
+    assert_eq!(
+        Sources::find(&["tests/sources/addons/*/config.cpp"]).unwrap(),
+        paths(&["tests/sources/addons/main/config.cpp"])
+    );
+    assert_eq!(
+        Sources::find(&["tests/sources/**/*.hpp", "tests/sources/addons/main/ui/dialog.hpp"]).unwrap(),
+        paths(&["tests/sources/addons/main/ui/dialog.hpp"])
+    );
+    assert!(Sources::find(&["tests/sources/**/*.sqm"]).is_err());
+    assert!(Sources::find(&["tests/sources/x[[]y.cpp"]).is_err());
+}
+
+#[test]
+fn files() {
+    // Files named directly are linted even when they are ignored
+    assert_eq!(
+        Sources::find(&["tests/sources/addons/old/config.cpp", "tests/lint.cpp"]).unwrap(),
+        paths(&["tests/sources/addons/old/config.cpp", "tests/lint.cpp"])
+    );
+    assert!(Sources::find(&["tests/sources/missing.cpp"]).is_err());
+}
+
+#[test]
+fn multiple() {
+    lint(&["tests/sources/addons", "tests/lint.cpp", "-j", "2"]).unwrap();
+    let err = lint(&["tests/sources/addons", "tests/lint.cpp", "--deny-warnings"]).unwrap_err();
+    assert!(matches!(
+        err,
+        ArmaLintError::Lint {
+            errors: 0,
+            warnings: 4,
+            ..
+        }
+    ));
+    let err = lint(&["tests/sources/addons/*/config.cpp", "tests/syntax.cpp", "-j", "1"]).unwrap_err();
+    assert!(matches!(
+        err,
+        ArmaLintError::Lint {
+            errors: 6,
+            warnings: 1,
+            ..
+        }
+    ));
+    assert_eq!(lint(&["tests/sources/addons/old/config.cpp"]).unwrap_err().exit_code(), 1);
+}
+
+#[test]
+fn unsupported() {
+    let err = lint(&["tests/sources/addons/main/readme.txt"]).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid Input: Unable to process `txt` files");
+    let err = lint(&["tests/sources/addons/main/readme.txt", "tests/lint.cpp"]).unwrap_err();
+    assert_eq!(err.exit_code(), 3);
+    assert_eq!(err.to_string(), "1 file could not be linted");
+}
@@ -0,0 +1,6 @@
+class CfgPatches {
+    class main {
+        units[] = {};
+    };
+};
+#include "ui\dialog.hpp"
@@ -0,0 +1,4 @@
+#define speed 1
+class Dialog {
+    idd = -1;
+};
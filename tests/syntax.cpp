@@ -0,0 +1,11 @@
+class CfgPatches {
+    class test {
+        units[] = {};
+        requiredVersion = 1.0
+        author = ;
+        weapons[] = {"a", "b";
+    }
+};
+class CfgVehicles : {};
+class Car {};
+}
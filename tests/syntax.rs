@@ -0,0 +1,80 @@
+use armalint::config::{Code, Diagnostic};
+use armalint::error::ArmaLintError;
+
+const FILE: &str = "tests/syntax.cpp";
+
+fn errors(content: &str) -> Vec<Diagnostic> {
+    match armalint::config::parse(FILE, content) {
+        Err(ArmaLintError::SyntaxErrors(ast)) => ast.report.unwrap().errors,
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+}
+
+fn message(diagnostic: &Diagnostic) -> (usize, usize, &str) {
+    assert_eq!(diagnostic.code, Code::SyntaxError);
+    (diagnostic.span.start.0, diagnostic.span.start.1, diagnostic.message.as_str())
+}
+
+#[test]
+fn every_error() {
+    let content = std::fs::read_to_string(FILE).unwrap();
+    let errors = errors(&content);
+    assert_eq!(
+        errors.iter().map(message).collect::<Vec<_>>(),
+        vec![
+            (4, 9, "missing `;` after property"),
+            (5, 9, "missing value for `author`"),
+            (6, 9, "missing `}` to close the array"),
+            (7, 5, "missing `;` after class body"),
+            (9, 1, "missing the name of the parent class"),
+            (11, 1, "unexpected `}`, there is no class to close"),
+        ]
+    );
+    assert_eq!(errors[0].span.end, (4, 30));
+    assert_eq!(errors[0].fix.as_ref().unwrap().replacement, "requiredVersion = 1.0;");
+}
+
+#[test]
+fn unclosed() {
+    let errors = errors("class A {\n    class B {\n        x = 1;\n");
+    assert_eq!(
+        errors.iter().map(message).collect::<Vec<_>>(),
+        vec![
+            (2, 5, "missing `};` to close `class B`"),
+            (1, 1, "missing `};` to close `class A`")
+        ]
+    );
+}
+
+#[test]
+fn directive() {
+    let errors = errors("#include\nvalue = 1;\n#define\n");
+    assert_eq!(
+        errors.iter().map(message).collect::<Vec<_>>(),
+        vec![(1, 1, "invalid directive `#include`"), (3, 1, "invalid directive `#define`")]
+    );
+}
+
+#[test]
+fn display() {
+    let err = armalint::config::parse(FILE, "a = 1;\nb = ;\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "1 syntax error in `tests/syntax.cpp`\n  2:1: missing value for `b`"
+    );
+}
+
+#[test]
+fn commands() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("syntax.bin");
+    let args = |args: &[&str]| -> Vec<String> {
+        std::iter::once("armalint")
+            .chain(args.iter().cloned())
+            .map(String::from)
+            .collect()
+    };
+    assert!(armalint::execute(&args(&["lint", FILE])).is_err());
+    assert!(armalint::execute(&args(&["rapify", FILE, output.to_str().unwrap()])).is_err());
+    assert!(!output.exists());
+}